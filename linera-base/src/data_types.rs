@@ -121,6 +121,19 @@ pub enum Round {
     Validator(u32),
 }
 
+/// The category of a [`Round`], without its number.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum RoundKind {
+    /// The initial fast round.
+    Fast,
+    /// A multi-leader round.
+    MultiLeader,
+    /// A single-leader round.
+    SingleLeader,
+    /// A round where the validators rotate as leaders.
+    Validator,
+}
+
 /// A duration in microseconds.
 #[derive(
     Eq,
@@ -640,6 +653,16 @@ impl Round {
         }
     }
 
+    /// The category of the round.
+    pub fn kind(&self) -> RoundKind {
+        match self {
+            Round::Fast => RoundKind::Fast,
+            Round::MultiLeader(_) => RoundKind::MultiLeader,
+            Round::SingleLeader(_) => RoundKind::SingleLeader,
+            Round::Validator(_) => RoundKind::Validator,
+        }
+    }
+
     /// The category of the round as a string.
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -0,0 +1,89 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares checking many certificates against the same committee with
+//! `LiteCertificate::check` and with a `CommitteeVerifier`.
+
+use std::collections::BTreeMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linera_base::{
+    crypto::{AccountSecretKey, CryptoHash, Ed25519SecretKey, ValidatorKeypair},
+    data_types::{Epoch, Round},
+    identifiers::ChainId,
+};
+use linera_chain::{
+    data_types::{LiteValue, LiteVote},
+    types::{CertificateKind, CommitteeVerifier, LiteCertificate},
+};
+use linera_execution::{
+    committee::{Committee, ValidatorState},
+    ResourceControlPolicy,
+};
+
+/// The number of validators in the committee.
+const VALIDATOR_COUNT: usize = 100;
+
+/// The number of certificates checked in each iteration.
+const CERTIFICATE_COUNT: u64 = 100;
+
+/// Returns a committee of `VALIDATOR_COUNT` validators, and certificates signed by a quorum
+/// of them.
+fn make_certificates() -> (Committee, Vec<LiteCertificate<'static>>) {
+    let account_public_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate()).public();
+    let key_pairs = (0..VALIDATOR_COUNT)
+        .map(|_| ValidatorKeypair::generate())
+        .collect::<Vec<_>>();
+    let validators = key_pairs
+        .iter()
+        .map(|key_pair| {
+            let state = ValidatorState {
+                network_address: key_pair.public_key.to_string(),
+                votes: 1,
+                account_public_key,
+            };
+            (key_pair.public_key, state)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    let quorum = usize::try_from(committee.quorum_threshold()).unwrap();
+    let chain_id = ChainId(CryptoHash::from([0, 0, 0, 1]));
+    let certificates = (0..CERTIFICATE_COUNT)
+        .map(|index| {
+            let value = LiteValue {
+                value_hash: CryptoHash::from([index, 0, 0, 0]),
+                chain_id,
+                kind: CertificateKind::Confirmed,
+            };
+            let votes = key_pairs[..quorum]
+                .iter()
+                .map(|key_pair| LiteVote::new(value.clone(), Round::Fast, &key_pair.secret_key));
+            LiteCertificate::try_from_votes(votes).unwrap()
+        })
+        .collect();
+    (committee, certificates)
+}
+
+fn committee_verifier_benchmark(c: &mut Criterion) {
+    let (committee, certificates) = make_certificates();
+    let verifier = CommitteeVerifier::new(Epoch::ZERO, &committee);
+    let mut group = c.benchmark_group("certificate_checks");
+    group.bench_function("check", |bencher| {
+        bencher.iter(|| {
+            for certificate in black_box(&certificates) {
+                certificate.check(black_box(&committee)).unwrap();
+            }
+        })
+    });
+    group.bench_function("committee_verifier", |bencher| {
+        bencher.iter(|| {
+            for certificate in black_box(&certificates) {
+                black_box(&verifier).verify(certificate).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, committee_verifier_benchmark);
+criterion_main!(benches);
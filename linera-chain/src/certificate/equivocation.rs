@@ -0,0 +1,116 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+
+use linera_base::{crypto::ValidatorPublicKey, data_types::BlockHeight, ensure};
+use linera_execution::committee::Committee;
+use serde::{Deserialize, Serialize};
+
+use crate::{data_types::LiteVote, ChainError};
+
+/// Evidence that a validator signed two different values of the same kind, for the same
+/// chain, block height and round. Rounds are only meaningful for a given chain and height,
+/// which `LiteVote` does not carry, so the proof includes the heights of the two values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(with_testing, derive(Eq, PartialEq))]
+pub struct EquivocationProof {
+    /// The height of the value of the first vote.
+    pub first_height: BlockHeight,
+    /// The first of the conflicting votes.
+    pub first: LiteVote,
+    /// The height of the value of the second vote.
+    pub second_height: BlockHeight,
+    /// The second of the conflicting votes.
+    pub second: LiteVote,
+}
+
+/// Returns the proof that the validator equivocated if the two votes, given with the heights
+/// of their values, are from the same validator, chain, height and round, and of the same
+/// kind, but for different values. Signatures are not verified.
+pub fn equivocation_proof(
+    (first_height, first): (BlockHeight, LiteVote),
+    (second_height, second): (BlockHeight, LiteVote),
+) -> Option<EquivocationProof> {
+    let proof = EquivocationProof {
+        first_height,
+        first,
+        second_height,
+        second,
+    };
+    proof.is_conflicting().then_some(proof)
+}
+
+/// Splits `votes`, given with the heights of their values, into the votes of the validators
+/// that did not equivocate, in their original order, and a pair of conflicting votes for
+/// each validator that did. Only votes of the same kind, for the same chain, height and
+/// round are compared: e.g. a validator's votes on several chains, or its validated and
+/// confirmed votes for the same block, are all kept. Repeated votes for the same value and
+/// round are only kept once. Signatures are not verified.
+pub fn partition_votes(
+    votes: impl IntoIterator<Item = (BlockHeight, LiteVote)>,
+) -> (
+    Vec<(BlockHeight, LiteVote)>,
+    Vec<(ValidatorPublicKey, [LiteVote; 2])>,
+) {
+    let mut consistent = Vec::<(BlockHeight, LiteVote)>::new();
+    let mut positions = HashMap::new();
+    let mut conflicts = Vec::new();
+    let mut equivocators = HashSet::new();
+    for (height, vote) in votes {
+        if equivocators.contains(&vote.public_key) {
+            continue;
+        }
+        let key = (
+            vote.public_key,
+            vote.value.chain_id,
+            height,
+            vote.round,
+            vote.value.kind,
+        );
+        let Some(position) = positions.get(&key) else {
+            positions.insert(key, consistent.len());
+            consistent.push((height, vote));
+            continue;
+        };
+        let first = consistent[*position].clone();
+        if let Some(proof) = equivocation_proof(first, (height, vote)) {
+            equivocators.insert(proof.validator());
+            conflicts.push((proof.validator(), [proof.first, proof.second]));
+        }
+    }
+    consistent.retain(|(_, vote)| !equivocators.contains(&vote.public_key));
+    (consistent, conflicts)
+}
+
+impl EquivocationProof {
+    /// Returns the validator that equivocated.
+    pub fn validator(&self) -> ValidatorPublicKey {
+        self.first.public_key
+    }
+
+    /// Verifies that the votes conflict, i.e. that they are from the same validator, chain,
+    /// height and round, and of the same kind, but for different values, that the validator
+    /// belongs to the committee, and that both signatures are valid. Returns the validator
+    /// that equivocated.
+    pub fn verify(&self, committee: &Committee) -> Result<ValidatorPublicKey, ChainError> {
+        ensure!(self.is_conflicting(), ChainError::NotAnEquivocation);
+        let validator = self.validator();
+        ensure!(
+            committee.weight(&validator) > 0,
+            ChainError::UnknownSigner(validator)
+        );
+        self.first.check()?;
+        self.second.check()?;
+        Ok(validator)
+    }
+
+    fn is_conflicting(&self) -> bool {
+        self.first.public_key == self.second.public_key
+            && self.first.value.chain_id == self.second.value.chain_id
+            && self.first_height == self.second_height
+            && self.first.round == self.second.round
+            && self.first.value.kind == self.second.value.kind
+            && self.first.value.value_hash != self.second.value.value_hash
+    }
+}
@@ -94,8 +94,10 @@ impl<T: CertificateValue> GenericCertificate<T> {
             .is_ok()
     }
 
-    /// Verifies the certificate.
-    pub fn check(&self, committee: &Committee) -> Result<(), ChainError>
+    /// Verifies the certificate, exactly like
+    /// [`LiteCertificate::check`](crate::certificate::LiteCertificate::check) on its
+    /// [`lite_certificate`](Self::lite_certificate), and returns the certified value.
+    pub fn check(&self, committee: &Committee) -> Result<&T, ChainError>
     where
         T: CertificateValue,
     {
@@ -106,9 +108,12 @@ impl<T: CertificateValue> GenericCertificate<T> {
             &self.signatures,
             committee,
         )?;
-        Ok(())
+        Ok(&self.value)
     }
 
+    /// Returns the [`LiteCertificate`](crate::certificate::LiteCertificate) for the same value
+    /// and round, borrowing the signatures. This is the inverse of
+    /// [`LiteCertificate::with_value`](crate::certificate::LiteCertificate::with_value).
     pub fn lite_certificate(&self) -> crate::certificate::LiteCertificate<'_>
     where
         T: CertificateValue,
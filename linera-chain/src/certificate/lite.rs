@@ -2,21 +2,272 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use linera_base::{
-    crypto::{ValidatorPublicKey, ValidatorSignature},
-    data_types::Round,
+    bcs,
+    crypto::{BcsHashable, CryptoHash, ValidatorPublicKey, ValidatorSignature},
+    data_types::{Epoch, Round, RoundKind},
+    ensure,
+    identifiers::ChainId,
 };
 use linera_execution::committee::Committee;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use super::{CertificateValue, GenericCertificate};
+use super::{CertificateKind, CertificateValue, GenericCertificate};
 use crate::{
-    data_types::{check_signatures, LiteValue, LiteVote},
+    data_types::{
+        check_signatures, check_signatures_parallel, check_signatures_with_index,
+        check_signatures_with_threshold, invalid_signers, is_strictly_ordered,
+        LiteSignatureAggregator, LiteValue, LiteVote,
+    },
     ChainError,
 };
 
+/// The version of the layout of [`LiteCertificate::canonical_bytes`].
+pub const CANONICAL_BYTES_VERSION: u8 = 1;
+
+/// The compact encoding of a [`LiteCertificate`], relative to a committee.
+#[derive(Serialize, Deserialize)]
+struct CompactLiteCertificate {
+    value: LiteValue,
+    round: Round,
+    /// The bitmap of the signers, in the order of the committee's validators.
+    signers: Vec<u8>,
+    /// The signatures, in the order of the committee's validators.
+    signatures: Vec<ValidatorSignature>,
+}
+
+/// The human-readable encoding of a [`LiteCertificate`], see [`LiteCertificate::to_json`].
+#[derive(Serialize, Deserialize)]
+struct JsonLiteCertificate {
+    value_hash: CryptoHash,
+    chain_id: ChainId,
+    kind: CertificateKind,
+    /// The round, formatted like [`Round`]'s `Display` implementation.
+    round: String,
+    signatures: Vec<JsonSignature>,
+}
+
+/// A signature of a [`JsonLiteCertificate`].
+#[derive(Serialize, Deserialize)]
+struct JsonSignature {
+    validator: ValidatorPublicKey,
+    signature: ValidatorSignature,
+}
+
+/// Parses a round formatted by [`Round`]'s `Display` implementation.
+fn parse_round(round: &str) -> Option<Round> {
+    if round == "fast round" {
+        return Some(Round::Fast);
+    }
+    let (kind, number) = round.split_once(" round ")?;
+    let number = number.parse().ok()?;
+    match kind {
+        "multi-leader" => Some(Round::MultiLeader(number)),
+        "single-leader" => Some(Round::SingleLeader(number)),
+        "validator" => Some(Round::Validator(number)),
+        _ => None,
+    }
+}
+
+/// Combines the certificates for the same value and round, e.g. when collecting them from
+/// several validators. Certificates are grouped by value, i.e. chain ID, value hash and kind,
+/// and by round, and the result has one certificate per group, in the order in which the
+/// groups first appear. Validated and confirmed blocks have the same hash, so their
+/// certificates are only told apart by their kind.
+///
+/// Each certificate has the signatures of all the certificates of its group, with each signer
+/// only once. If a validator provided different signatures, the one from the certificate with
+/// the highest signer weight in the committee is kept. Signatures are not verified.
+pub fn dedup_by_value(
+    certificates: Vec<LiteCertificate<'_>>,
+    committee: &Committee,
+) -> Vec<LiteCertificate<'static>> {
+    let mut groups = Vec::<Vec<LiteCertificate>>::new();
+    let mut positions = HashMap::<(LiteValue, Round), usize>::new();
+    for certificate in certificates {
+        let key = (certificate.value.clone(), certificate.round);
+        match positions.entry(key) {
+            Entry::Occupied(entry) => groups[*entry.get()].push(certificate),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push(vec![certificate]);
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|mut group| {
+            group.sort_by_key(|certificate| std::cmp::Reverse(certificate.weight(committee)));
+            let mut signatures = BTreeMap::new();
+            for certificate in &group {
+                for (public_key, signature) in certificate.signatures.iter() {
+                    signatures.entry(*public_key).or_insert(*signature);
+                }
+            }
+            LiteCertificate {
+                value: group[0].value.clone(),
+                round: group[0].round,
+                signatures: Cow::Owned(signatures.into_iter().collect()),
+            }
+        })
+        .collect()
+}
+
+/// Remembers the certificates that were successfully verified against the committee of an
+/// epoch, so that checking them again skips the signature verification. The cache is emptied
+/// whenever it is used with a different epoch.
+#[derive(Debug, Default)]
+pub struct VerifiedCertificateCache {
+    epoch: Option<Epoch>,
+    verified: HashSet<CryptoHash>,
+}
+
+impl VerifiedCertificateCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of cached certificates.
+    pub fn len(&self) -> usize {
+        self.verified.len()
+    }
+
+    /// Returns whether no certificate is cached.
+    pub fn is_empty(&self) -> bool {
+        self.verified.is_empty()
+    }
+
+    /// Forgets all the cached certificates.
+    pub fn clear(&mut self) {
+        self.verified.clear();
+    }
+}
+
+/// Verifies certificates against the committee of an epoch, with the weights of its
+/// validators and its quorum threshold computed once, for when many certificates are checked
+/// against the same committee. A verifier must be recreated, e.g. with
+/// [`CommitteeVerifier::update`], when the epoch changes.
+#[derive(Clone, Debug)]
+pub struct CommitteeVerifier {
+    epoch: Epoch,
+    signer_index: HashMap<ValidatorPublicKey, u64>,
+    quorum_threshold: u64,
+}
+
+impl CommitteeVerifier {
+    /// Creates a verifier for the committee of the given epoch.
+    pub fn new(epoch: Epoch, committee: &Committee) -> Self {
+        Self {
+            epoch,
+            signer_index: committee.signer_index().clone(),
+            quorum_threshold: committee.quorum_threshold(),
+        }
+    }
+
+    /// Returns the epoch of the committee.
+    pub fn epoch(&self) -> Epoch {
+        self.epoch
+    }
+
+    /// Recreates the verifier if `epoch` is not the one it was created for. Returns whether
+    /// it was recreated.
+    pub fn update(&mut self, epoch: Epoch, committee: &Committee) -> bool {
+        if epoch == self.epoch {
+            return false;
+        }
+        *self = Self::new(epoch, committee);
+        true
+    }
+
+    /// Verifies the certificate, with the same result as [`LiteCertificate::check`] with the
+    /// committee of the verifier.
+    pub fn verify<'a>(
+        &self,
+        certificate: &'a LiteCertificate<'_>,
+    ) -> Result<&'a LiteValue, ChainError> {
+        check_signatures_with_index(
+            certificate.value.value_hash,
+            certificate.value.kind,
+            certificate.round,
+            &certificate.signatures,
+            &self.signer_index,
+            self.quorum_threshold,
+        )?;
+        Ok(&certificate.value)
+    }
+}
+
+/// The reason why votes cannot be aggregated into a [`LiteCertificate`].
+#[derive(Debug, Error)]
+pub enum VoteAggregationError {
+    #[error("No votes to aggregate")]
+    Empty,
+    #[error("Vote for value {found} instead of {expected}")]
+    ValueMismatch {
+        expected: CryptoHash,
+        found: CryptoHash,
+    },
+    #[error("Vote for chain {found} instead of {expected}")]
+    ChainIdMismatch { expected: ChainId, found: ChainId },
+    #[error("Vote for a value of kind {found:?} instead of {expected:?}")]
+    KindMismatch {
+        expected: CertificateKind,
+        found: CertificateKind,
+    },
+    #[error("Vote in {found:?} instead of {expected:?}")]
+    RoundMismatch { expected: Round, found: Round },
+    #[error("Validator {0} provided two different signatures")]
+    ConflictingSignatures(ValidatorPublicKey),
+    #[error("A certificate of kind {kind:?} cannot be in {round:?}")]
+    InvalidRoundForKind { kind: CertificateKind, round: Round },
+}
+
+/// The reason why a value does not match a [`LiteCertificate`].
+#[derive(Debug, Error)]
+pub enum ValueMatchError {
+    #[error("Value for chain {found} instead of {expected}")]
+    ChainIdMismatch { expected: ChainId, found: ChainId },
+    #[error("Value of kind {found:?} instead of {expected:?}")]
+    KindMismatch {
+        expected: CertificateKind,
+        found: CertificateKind,
+    },
+    #[error("Value with hash {found} instead of {expected}")]
+    HashMismatch {
+        expected: CryptoHash,
+        found: CryptoHash,
+    },
+}
+
+/// How much weight the signers of a certificate need, relative to the committee.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuorumMode {
+    /// More than two thirds of the total weight, tolerating Byzantine validators.
+    #[default]
+    Byzantine,
+    /// More than half of the total weight, for trusted validators.
+    Majority,
+}
+
+impl QuorumMode {
+    /// Returns the minimal total weight of the signers in the given committee.
+    pub fn required_weight(&self, committee: &Committee) -> u64 {
+        match self {
+            QuorumMode::Byzantine => committee.quorum_threshold(),
+            QuorumMode::Majority => committee.total_votes() / 2 + 1,
+        }
+    }
+}
+
 /// A certified statement from the committee, without the value.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(with_testing, derive(Eq, PartialEq))]
@@ -29,7 +280,11 @@ pub struct LiteCertificate<'a> {
     pub signatures: Cow<'a, [(ValidatorPublicKey, ValidatorSignature)]>,
 }
 
-impl LiteCertificate<'_> {
+impl<'de> BcsHashable<'de> for LiteCertificate<'de> {}
+
+impl<'a> LiteCertificate<'a> {
+    /// Creates a [`LiteCertificate`], sorting the signatures by validator. Repeated validators
+    /// are not detected: use [`LiteCertificate::try_new`] for untrusted signatures.
     pub fn new(
         value: LiteValue,
         round: Round,
@@ -45,27 +300,201 @@ impl LiteCertificate<'_> {
         }
     }
 
+    /// Creates a [`LiteCertificate`] that borrows the given signatures, which must be sorted
+    /// by validator and contain each validator at most once.
+    pub fn from_sorted_slice(
+        value: LiteValue,
+        round: Round,
+        signatures: &'a [(ValidatorPublicKey, ValidatorSignature)],
+    ) -> Self {
+        debug_assert!(
+            is_strictly_ordered(signatures),
+            "signatures must be sorted and deduplicated"
+        );
+        Self {
+            value,
+            round,
+            signatures: Cow::Borrowed(signatures),
+        }
+    }
+
+    /// Creates a [`LiteCertificate`] like [`LiteCertificate::new`], keeping a single copy of
+    /// repeated signatures. Returns an error if a validator provided two different signatures,
+    /// or if the kind of the value cannot be certified in this round.
+    pub fn try_new(
+        value: LiteValue,
+        round: Round,
+        mut signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+    ) -> Result<Self, ChainError> {
+        ensure!(
+            value.kind.is_valid_in(round),
+            ChainError::InvalidRoundForKind {
+                kind: value.kind,
+                round,
+            }
+        );
+        signatures.sort_by_key(|&(validator_name, _)| validator_name);
+        let mut deduplicated = Vec::<(ValidatorPublicKey, ValidatorSignature)>::new();
+        for (validator_name, signature) in signatures {
+            if let Some((last_name, last_signature)) = deduplicated.last() {
+                if *last_name == validator_name {
+                    ensure!(
+                        *last_signature == signature,
+                        ChainError::ConflictingSignatures(validator_name)
+                    );
+                    continue;
+                }
+            }
+            deduplicated.push((validator_name, signature));
+        }
+        let signatures = Cow::Owned(deduplicated);
+        Ok(Self {
+            value,
+            round,
+            signatures,
+        })
+    }
+
     /// Creates a [`LiteCertificate`] from a list of votes, without cryptographically checking the
-    /// signatures. Returns `None` if the votes are empty or don't have matching values and rounds.
+    /// signatures. Returns `None` if the votes are empty, don't have matching values and rounds,
+    /// or contain two different signatures from the same validator. Matching values have the
+    /// same hash, chain ID and kind.
     pub fn try_from_votes(votes: impl IntoIterator<Item = LiteVote>) -> Option<Self> {
+        Self::try_from_votes_checked(votes).ok()
+    }
+
+    /// Creates a [`LiteCertificate`] from a list of votes, like
+    /// [`LiteCertificate::try_from_votes`], once their signers form a quorum of the committee.
+    /// Stops pulling votes as soon as the quorum is reached, and returns `None` if it never
+    /// is. Signers outside the committee count for nothing, and the signatures are not
+    /// checked.
+    pub fn try_from_votes_weighted(
+        votes: impl IntoIterator<Item = LiteVote>,
+        committee: &Committee,
+    ) -> Option<Self> {
+        let mut signers = HashSet::new();
+        let mut weight = 0;
+        let mut quorum_votes = Vec::new();
+        for vote in votes {
+            if signers.insert(vote.public_key) {
+                weight += committee.weight(&vote.public_key);
+            }
+            quorum_votes.push(vote);
+            if weight >= committee.quorum_threshold() {
+                return Self::try_from_votes(quorum_votes);
+            }
+        }
+        None
+    }
+
+    /// Creates a [`LiteCertificate`] from a list of votes, without cryptographically checking the
+    /// signatures. Returns the reason why the votes cannot be aggregated, if any.
+    pub fn try_from_votes_checked(
+        votes: impl IntoIterator<Item = LiteVote>,
+    ) -> Result<Self, VoteAggregationError> {
         let mut votes = votes.into_iter();
         let LiteVote {
             value,
             round,
             public_key,
             signature,
-        } = votes.next()?;
+        } = votes.next().ok_or(VoteAggregationError::Empty)?;
         let mut signatures = vec![(public_key, signature)];
         for vote in votes {
-            if vote.value.value_hash != value.value_hash || vote.round != round {
-                return None;
-            }
+            ensure!(
+                vote.value.value_hash == value.value_hash,
+                VoteAggregationError::ValueMismatch {
+                    expected: value.value_hash,
+                    found: vote.value.value_hash,
+                }
+            );
+            ensure!(
+                vote.value.chain_id == value.chain_id,
+                VoteAggregationError::ChainIdMismatch {
+                    expected: value.chain_id,
+                    found: vote.value.chain_id,
+                }
+            );
+            ensure!(
+                vote.value.kind == value.kind,
+                VoteAggregationError::KindMismatch {
+                    expected: value.kind,
+                    found: vote.value.kind,
+                }
+            );
+            ensure!(
+                vote.round == round,
+                VoteAggregationError::RoundMismatch {
+                    expected: round,
+                    found: vote.round,
+                }
+            );
             signatures.push((vote.public_key, vote.signature));
         }
-        Some(LiteCertificate::new(value, round, signatures))
+        LiteCertificate::try_new(value, round, signatures).map_err(|error| match error {
+            ChainError::ConflictingSignatures(public_key) => {
+                VoteAggregationError::ConflictingSignatures(public_key)
+            }
+            ChainError::InvalidRoundForKind { kind, round } => {
+                VoteAggregationError::InvalidRoundForKind { kind, round }
+            }
+            _ => unreachable!("`try_new` only fails on conflicting signatures or invalid rounds"),
+        })
+    }
+
+    /// Returns the hash of the certified value.
+    pub fn value_hash(&self) -> CryptoHash {
+        self.value.value_hash
+    }
+
+    /// Returns the ID of the chain of the certified value.
+    pub fn chain_id(&self) -> ChainId {
+        self.value.chain_id
+    }
+
+    /// Returns the kind of the certified value.
+    pub fn kind(&self) -> CertificateKind {
+        self.value.kind
+    }
+
+    /// Returns the round in which the value was certified.
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
+    /// Returns the category of the round in which the value was certified.
+    pub fn round_kind(&self) -> RoundKind {
+        self.round.kind()
+    }
+
+    /// Returns whether the value was certified in the fast round.
+    pub fn is_fast(&self) -> bool {
+        self.round.is_fast()
+    }
+
+    /// Returns whether the value was certified in a multi-leader round.
+    pub fn is_multi_leader(&self) -> bool {
+        self.round.is_multi_leader()
+    }
+
+    /// Creates a [`LiteCertificate`] from a stream of votes, verifying each of them as it is
+    /// pulled from the iterator. Stops pulling votes as soon as they form a quorum of the
+    /// committee. Fails on the first invalid vote, or if the votes never form a quorum.
+    pub fn try_from_votes_until_quorum(
+        votes: impl IntoIterator<Item = LiteVote>,
+        committee: &Committee,
+    ) -> Result<LiteCertificate<'static>, ChainError> {
+        let mut aggregator = LiteSignatureAggregator::new(committee);
+        for vote in votes {
+            if let Some(certificate) = aggregator.append(vote)? {
+                return Ok(certificate);
+            }
+        }
+        Err(ChainError::CertificateRequiresQuorum)
     }
 
-    /// Verifies the certificate.
+    /// Verifies the certificate. A certificate with a signer outside the committee is
+    /// rejected with [`ChainError::UnknownSigner`].
     pub fn check(&self, committee: &Committee) -> Result<&LiteValue, ChainError> {
         check_signatures(
             self.value.value_hash,
@@ -77,19 +506,485 @@ impl LiteCertificate<'_> {
         Ok(&self.value)
     }
 
+    /// Verifies the certificate like [`LiteCertificate::check`], but requires the signers to
+    /// have a total weight of at least `min_weight` instead of the committee's quorum. If all
+    /// signatures are valid but the weight is lower, this fails with
+    /// [`ChainError::InsufficientSignerWeight`].
+    pub fn check_with_threshold(
+        &self,
+        committee: &Committee,
+        min_weight: u64,
+    ) -> Result<&LiteValue, ChainError> {
+        check_signatures_with_threshold(
+            self.value.value_hash,
+            self.value.kind,
+            self.round,
+            &self.signatures,
+            committee,
+            min_weight,
+        )?;
+        Ok(&self.value)
+    }
+
+    /// Verifies the certificate like [`LiteCertificate::check_with_threshold`], requiring the
+    /// weight of a quorum of the committee in the given mode.
+    pub fn check_with_quorum_mode(
+        &self,
+        committee: &Committee,
+        mode: QuorumMode,
+    ) -> Result<&LiteValue, ChainError> {
+        self.check_with_threshold(committee, mode.required_weight(committee))
+    }
+
+    /// Verifies the certificate like [`LiteCertificate::check`], verifying the signatures on
+    /// several threads. The result, including which error is reported, is the same as `check`.
+    pub fn check_parallel(&self, committee: &Committee) -> Result<&LiteValue, ChainError> {
+        check_signatures_parallel(
+            self.value.value_hash,
+            self.value.kind,
+            self.round,
+            &self.signatures,
+            committee,
+        )?;
+        Ok(&self.value)
+    }
+
+    /// Verifies the certificate like [`LiteCertificate::check`] with the committee of the
+    /// given epoch, unless the same certificate was already verified for this epoch using the
+    /// given cache.
+    pub fn check_cached(
+        &self,
+        epoch: Epoch,
+        committee: &Committee,
+        cache: &mut VerifiedCertificateCache,
+    ) -> Result<&LiteValue, ChainError> {
+        if cache.epoch != Some(epoch) {
+            cache.verified.clear();
+            cache.epoch = Some(epoch);
+        }
+        let key = CryptoHash::new(self);
+        if !cache.verified.contains(&key) {
+            self.check(committee)?;
+            cache.verified.insert(key);
+        }
+        Ok(&self.value)
+    }
+
+    /// Verifies the certificate exactly like [`LiteCertificate::check`], and also returns the
+    /// public keys of the validators that signed it, in the order of `self.signatures`.
+    pub fn check_and_signers(
+        &self,
+        committee: &Committee,
+    ) -> Result<(&LiteValue, Vec<ValidatorPublicKey>), ChainError> {
+        let value = self.check(committee)?;
+        let signers = self.signers().copied().collect();
+        Ok((value, signers))
+    }
+
+    /// Verifies the certificate exactly like [`LiteCertificate::check`], and returns the
+    /// validators that signed it with their weights in the committee, sorted by public key.
+    pub fn contributing_validators(
+        &self,
+        committee: &Committee,
+    ) -> Result<Vec<(ValidatorPublicKey, u64)>, ChainError> {
+        self.check(committee)?;
+        let mut validators = self
+            .signers()
+            .map(|public_key| (*public_key, committee.weight(public_key)))
+            .collect::<Vec<_>>();
+        validators.sort_unstable_by_key(|(public_key, _)| *public_key);
+        Ok(validators)
+    }
+
+    /// Returns the public keys of the validators that signed the certificate, in the order
+    /// of `self.signatures`.
+    pub fn signers(&self) -> impl Iterator<Item = &ValidatorPublicKey> {
+        self.signatures.iter().map(|(public_key, _)| public_key)
+    }
+
+    /// Returns the validators that signed both this certificate and `other`, in increasing
+    /// order. The signatures are not verified.
+    pub fn intersect_signers(&self, other: &LiteCertificate) -> Vec<ValidatorPublicKey> {
+        let signers = self.signers().collect::<BTreeSet<_>>();
+        let other_signers = other.signers().collect::<BTreeSet<_>>();
+        signers
+            .intersection(&other_signers)
+            .map(|key| **key)
+            .collect()
+    }
+
+    /// Returns the validators that signed exactly one of this certificate and `other`, in
+    /// increasing order. The signatures are not verified.
+    pub fn symmetric_difference_signers(&self, other: &LiteCertificate) -> Vec<ValidatorPublicKey> {
+        let signers = self.signers().collect::<BTreeSet<_>>();
+        let other_signers = other.signers().collect::<BTreeSet<_>>();
+        signers
+            .symmetric_difference(&other_signers)
+            .map(|key| **key)
+            .collect()
+    }
+
+    /// Returns the signers whose signature does not verify, or who are not in the
+    /// committee, in the order of `self.signatures`. This is meant to diagnose a certificate
+    /// rejected by [`LiteCertificate::check`]: it is empty if all signatures are valid.
+    pub fn invalid_signers(&self, committee: &Committee) -> Vec<ValidatorPublicKey> {
+        invalid_signers(
+            self.value.value_hash,
+            self.value.kind,
+            self.round,
+            &self.signatures,
+            committee,
+        )
+    }
+
+    /// Returns, for each validator of the committee in the committee's order, whether it
+    /// signed the certificate. Signers outside the committee are ignored, and the signatures
+    /// are not verified.
+    pub fn signer_bitmap(&self, committee: &Committee) -> Vec<bool> {
+        let signers = self.signers().collect::<BTreeSet<_>>();
+        committee
+            .validators()
+            .keys()
+            .map(|public_key| signers.contains(public_key))
+            .collect()
+    }
+
+    /// Returns the number of signatures in the certificate.
+    pub fn signature_count(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Returns the length of the BCS encoding of the certificate, without serializing it
+    /// into a buffer.
+    pub fn serialized_size(&self) -> usize {
+        bcs::serialized_size(self).expect("Serializing a lite certificate should not fail")
+    }
+
+    /// Returns a canonical encoding of the certificate, independent of its serialization,
+    /// e.g. to compute fingerprints or external attestations. The layout is:
+    ///
+    /// * the version of the layout, [`CANONICAL_BYTES_VERSION`], as one byte;
+    /// * the 32 bytes of the value hash, then the 32 bytes of the chain ID;
+    /// * the certificate kind as one byte: 0 for timeout, 1 for validated, 2 for confirmed;
+    /// * the round kind as one byte: 0 for fast, 1 for multi-leader, 2 for single-leader,
+    ///   3 for validator rounds, followed by the round number as a big-endian `u32`;
+    /// * the number of signatures as a big-endian `u32`;
+    /// * for each signature, in the order of the compressed public keys, the 33 bytes of
+    ///   the compressed public key, then the 64 bytes of the signature.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut signatures = self
+            .signatures
+            .iter()
+            .map(|(public_key, signature)| (public_key.as_bytes(), signature.as_bytes()))
+            .collect::<Vec<_>>();
+        signatures.sort_unstable();
+        let round_kind = match self.round.kind() {
+            RoundKind::Fast => 0u8,
+            RoundKind::MultiLeader => 1,
+            RoundKind::SingleLeader => 2,
+            RoundKind::Validator => 3,
+        };
+        let mut bytes = vec![CANONICAL_BYTES_VERSION];
+        bytes.extend_from_slice(self.value.value_hash.as_bytes().as_slice());
+        bytes.extend_from_slice(self.value.chain_id.0.as_bytes().as_slice());
+        bytes.push(self.value.kind as u8);
+        bytes.push(round_kind);
+        bytes.extend_from_slice(&self.round.number().to_be_bytes());
+        let count = u32::try_from(signatures.len()).expect("too many signatures");
+        bytes.extend_from_slice(&count.to_be_bytes());
+        for (public_key, signature) in signatures {
+            bytes.extend_from_slice(&public_key);
+            bytes.extend_from_slice(&signature);
+        }
+        bytes
+    }
+
+    /// Returns a human-readable JSON representation of the certificate, for debugging and
+    /// tooling: hashes, public keys and signatures are hex-encoded, and the signatures keep
+    /// their order. This is not the wire format; use [`LiteCertificate::from_json`] to decode it.
+    pub fn to_json(&self) -> serde_json::Value {
+        let json = JsonLiteCertificate {
+            value_hash: self.value.value_hash,
+            chain_id: self.value.chain_id,
+            kind: self.value.kind,
+            round: self.round.to_string(),
+            signatures: self
+                .signatures
+                .iter()
+                .map(|(validator, signature)| JsonSignature {
+                    validator: *validator,
+                    signature: *signature,
+                })
+                .collect(),
+        };
+        serde_json::to_value(json).expect("Serializing a lite certificate should not fail")
+    }
+
+    /// Decodes a certificate from the representation returned by [`LiteCertificate::to_json`].
+    /// The result is identical to the original certificate, including the order of the
+    /// signatures. The signatures are not verified.
+    pub fn from_json(json: &serde_json::Value) -> Result<LiteCertificate<'static>, ChainError> {
+        let json = JsonLiteCertificate::deserialize(json)
+            .map_err(|error| ChainError::InvalidCertificateJson(error.to_string()))?;
+        let round = parse_round(&json.round).ok_or_else(|| {
+            ChainError::InvalidCertificateJson(format!("invalid round: {:?}", json.round))
+        })?;
+        let signatures = json
+            .signatures
+            .into_iter()
+            .map(|signature| (signature.validator, signature.signature))
+            .collect();
+        Ok(LiteCertificate {
+            value: LiteValue {
+                value_hash: json.value_hash,
+                chain_id: json.chain_id,
+                kind: json.kind,
+            },
+            round,
+            signatures: Cow::Owned(signatures),
+        })
+    }
+
+    /// Verifies a batch of certificates against the same committee, stopping at the first
+    /// invalid one. The returned error reports the index of that certificate in `certificates`.
+    ///
+    /// Validator signatures on different values cannot be verified together, so the
+    /// certificates are checked one after the other.
+    pub fn check_batch(
+        certificates: &[LiteCertificate],
+        committee: &Committee,
+    ) -> Result<(), ChainError> {
+        for (index, certificate) in certificates.iter().enumerate() {
+            certificate.check(committee).map_err(|error| {
+                ChainError::InvalidCertificateInBatch {
+                    index,
+                    error: Box::new(error),
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Verifies the certificate against each of the given committees in turn, e.g. those of
+    /// two consecutive epochs. Returns the index of the first committee for which the
+    /// certificate is valid, or the reason why it failed for each of them.
+    pub fn check_against_any(
+        &self,
+        committees: &[&Committee],
+    ) -> Result<(usize, &LiteValue), ChainError> {
+        let mut errors = Vec::new();
+        for (index, committee) in committees.iter().enumerate() {
+            match self.check(committee) {
+                Ok(value) => return Ok((index, value)),
+                Err(error) => errors.push(error),
+            }
+        }
+        Err(ChainError::CertificateInvalidForAllCommittees(errors))
+    }
+
+    /// Returns the total weight of the validators that signed the certificate, in the
+    /// given committee. Signers outside the committee are ignored, and the signatures are
+    /// not verified.
+    pub fn weight(&self, committee: &Committee) -> u64 {
+        self.signers()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|public_key| committee.weight(public_key))
+            .sum()
+    }
+
+    /// Returns whether the signers of the certificate form a quorum of the given committee,
+    /// in the given mode. The signatures are not verified.
+    pub fn has_quorum(&self, committee: &Committee, mode: QuorumMode) -> bool {
+        self.weight(committee) >= mode.required_weight(committee)
+    }
+
+    /// Drops signatures until the remaining ones form a minimal quorum of the committee,
+    /// keeping the validators with the highest weights. Removing any other signature from the
+    /// result would break the quorum. The certificate is left unchanged if it has no quorum.
+    pub fn trim_to_quorum(&mut self, committee: &Committee) {
+        let mut signers = self
+            .signatures
+            .iter()
+            .map(|(public_key, _)| (*public_key, committee.weight(public_key)))
+            .collect::<Vec<_>>();
+        signers.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+        let mut weight = 0;
+        let mut kept = BTreeSet::new();
+        for (public_key, signer_weight) in signers {
+            if weight >= committee.quorum_threshold() {
+                break;
+            }
+            if signer_weight > 0 && kept.insert(public_key) {
+                weight += signer_weight;
+            }
+        }
+        if weight < committee.quorum_threshold() {
+            return;
+        }
+        self.signatures
+            .to_mut()
+            .retain(|(public_key, _)| kept.remove(public_key));
+    }
+
+    /// Drops the signatures of the validators for which `keep` returns `false`, keeping the
+    /// others in order. Checking the certificate afterwards only verifies the retained
+    /// signatures, e.g. to find out whether a subset of the signers alone forms a quorum.
+    pub fn retain_signers(&mut self, keep: impl Fn(&ValidatorPublicKey) -> bool) {
+        if self
+            .signatures
+            .iter()
+            .all(|(public_key, _)| keep(public_key))
+        {
+            return;
+        }
+        self.signatures
+            .to_mut()
+            .retain(|(public_key, _)| keep(public_key));
+    }
+
+    /// Combines the signatures of two certificates for the same value and round. A validator
+    /// that signed both certificates must have provided the same signature.
+    pub fn merge(self, other: LiteCertificate) -> Result<LiteCertificate<'static>, ChainError> {
+        ensure!(
+            self.value == other.value && self.round == other.round,
+            ChainError::CertificateMergeMismatch
+        );
+        let mut signatures = self.signatures.into_owned();
+        signatures.extend_from_slice(&other.signatures);
+        LiteCertificate::try_new(self.value, self.round, signatures)
+    }
+
+    /// Adds the signature of a vote for the same value and round, verifying only the new
+    /// signature. Returns whether the weight of the signers increased: a vote from a
+    /// validator that already signed the certificate is ignored.
+    pub fn add_verified_vote(
+        &mut self,
+        vote: LiteVote,
+        committee: &Committee,
+    ) -> Result<bool, ChainError> {
+        ensure!(
+            vote.value == self.value && vote.round == self.round,
+            ChainError::VoteMismatch
+        );
+        vote.check()?;
+        ensure!(
+            committee.weight(&vote.public_key) > 0,
+            ChainError::UnknownSigner(vote.public_key)
+        );
+        if !is_strictly_ordered(&self.signatures) {
+            // The signatures were not sorted, e.g. in a deserialized certificate: keep their
+            // order and append the new one.
+            if self
+                .signers()
+                .any(|public_key| *public_key == vote.public_key)
+            {
+                return Ok(false);
+            }
+            self.signatures
+                .to_mut()
+                .push((vote.public_key, vote.signature));
+            return Ok(true);
+        }
+        let Err(index) = self
+            .signatures
+            .binary_search_by(|(public_key, _)| public_key.cmp(&vote.public_key))
+        else {
+            return Ok(false);
+        };
+        self.signatures
+            .to_mut()
+            .insert(index, (vote.public_key, vote.signature));
+        Ok(true)
+    }
+
+    /// Encodes the certificate compactly, replacing the public keys of the signers by a
+    /// bitmap of their positions in the committee. All signers must be distinct members of
+    /// the committee.
+    pub fn to_compact(&self, committee: &Committee) -> Result<Vec<u8>, ChainError> {
+        ensure!(
+            is_strictly_ordered(&self.signatures),
+            ChainError::CertificateValidatorReuse
+        );
+        let mut signers = vec![0u8; committee.validators().len().div_ceil(8)];
+        let mut signatures = self.signatures.iter().peekable();
+        let mut compact_signatures = Vec::with_capacity(self.signatures.len());
+        for (position, public_key) in committee.validators().keys().enumerate() {
+            if let Some((_, signature)) = signatures.next_if(|(signer, _)| signer == public_key) {
+                signers[position / 8] |= 1 << (position % 8);
+                compact_signatures.push(*signature);
+            }
+        }
+        if let Some((public_key, _)) = signatures.next() {
+            return Err(ChainError::UnknownSigner(*public_key));
+        }
+        let compact = CompactLiteCertificate {
+            value: self.value.clone(),
+            round: self.round,
+            signers,
+            signatures: compact_signatures,
+        };
+        Ok(bcs::to_bytes(&compact)?)
+    }
+
+    /// Decodes a certificate encoded with [`LiteCertificate::to_compact`] for the same
+    /// committee.
+    pub fn from_compact(
+        bytes: &[u8],
+        committee: &Committee,
+    ) -> Result<LiteCertificate<'static>, ChainError> {
+        let compact: CompactLiteCertificate = bcs::from_bytes(bytes)?;
+        let validator_count = committee.validators().len();
+        ensure!(
+            compact.signers.len() == validator_count.div_ceil(8),
+            ChainError::InvalidCompactCertificate(format!(
+                "expected a bitmap for {validator_count} validators"
+            ))
+        );
+        let public_keys = committee
+            .validators()
+            .keys()
+            .enumerate()
+            .filter(|(position, _)| compact.signers[position / 8] & (1 << (position % 8)) != 0)
+            .map(|(_, public_key)| *public_key)
+            .collect::<Vec<_>>();
+        let has_padding = compact
+            .signers
+            .last()
+            .is_some_and(|byte| validator_count % 8 != 0 && byte >> (validator_count % 8) != 0);
+        ensure!(
+            !has_padding && public_keys.len() == compact.signatures.len(),
+            ChainError::InvalidCompactCertificate(
+                "the signers do not match the signatures".to_string()
+            )
+        );
+        let signatures = public_keys.into_iter().zip(compact.signatures).collect();
+        Ok(LiteCertificate {
+            value: compact.value,
+            round: compact.round,
+            signatures: Cow::Owned(signatures),
+        })
+    }
+
+    /// Returns whether this certificate is from a later round than `other`, for the same chain.
+    pub fn supersedes(&self, other: &LiteCertificate) -> bool {
+        self.value.chain_id == other.value.chain_id && self.round > other.round
+    }
+
+    /// Returns whether both certificates are for the same value, regardless of their rounds
+    /// and signatures.
+    pub fn same_value(&self, other: &LiteCertificate) -> bool {
+        self.value == other.value
+    }
+
     /// Checks whether the value matches this certificate.
     pub fn check_value<T: CertificateValue>(&self, value: &T) -> bool {
-        self.value.chain_id == value.chain_id()
-            && T::KIND == self.value.kind
-            && self.value.value_hash == value.hash()
+        self.matches(value)
     }
 
     /// Returns the [`GenericCertificate`] with the specified value, if it matches.
     pub fn with_value<T: CertificateValue>(self, value: T) -> Option<GenericCertificate<T>> {
-        if self.value.chain_id != value.chain_id()
-            || T::KIND != self.value.kind
-            || self.value.value_hash != value.hash()
-        {
+        if !self.matches(&value) {
             return None;
         }
         Some(GenericCertificate::new(
@@ -99,6 +994,54 @@ impl LiteCertificate<'_> {
         ))
     }
 
+    /// Returns the [`GenericCertificate`] with the specified value, or which part of the
+    /// value does not match this certificate.
+    pub fn with_value_checked<T: CertificateValue>(
+        self,
+        value: T,
+    ) -> Result<GenericCertificate<T>, ValueMatchError> {
+        self.match_value(&value)?;
+        Ok(GenericCertificate::new(
+            value,
+            self.round,
+            self.signatures.into_owned(),
+        ))
+    }
+
+    /// Returns whether the chain ID, the kind and the hash of the value match this
+    /// certificate. This is the check shared by [`LiteCertificate::check_value`] and
+    /// [`LiteCertificate::with_value`].
+    fn matches<T: CertificateValue>(&self, value: &T) -> bool {
+        self.match_value(value).is_ok()
+    }
+
+    /// Checks that the chain ID, the kind and the hash of the value match this certificate.
+    fn match_value<T: CertificateValue>(&self, value: &T) -> Result<(), ValueMatchError> {
+        ensure!(
+            self.value.chain_id == value.chain_id(),
+            ValueMatchError::ChainIdMismatch {
+                expected: self.value.chain_id,
+                found: value.chain_id(),
+            }
+        );
+        ensure!(
+            T::KIND == self.value.kind,
+            ValueMatchError::KindMismatch {
+                expected: self.value.kind,
+                found: T::KIND,
+            }
+        );
+        let hash = value.hash();
+        ensure!(
+            self.value.value_hash == hash,
+            ValueMatchError::HashMismatch {
+                expected: self.value.value_hash,
+                found: hash,
+            }
+        );
+        Ok(())
+    }
+
     /// Returns a [`LiteCertificate`] that owns the list of signatures.
     pub fn cloned(&self) -> LiteCertificate<'static> {
         LiteCertificate {
@@ -108,3 +1051,104 @@ impl LiteCertificate<'_> {
         }
     }
 }
+
+/// The number of signers shown by the [`fmt::Display`] implementation of [`LiteCertificate`].
+const DISPLAYED_SIGNERS: usize = 3;
+
+/// Shows a summary of the certificate, with the value hash and signers shortened. Use the
+/// [`fmt::Debug`] implementation for the full certificate.
+impl fmt::Display for LiteCertificate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "certificate for {:.8}.. on chain {} in {} ({} signers",
+            self.value.value_hash,
+            self.value.chain_id,
+            self.round,
+            self.signatures.len()
+        )?;
+        for (index, (public_key, _)) in self.signatures.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { ", " };
+            if index == DISPLAYED_SIGNERS {
+                write!(f, "{separator}…")?;
+                break;
+            }
+            write!(f, "{separator}{}..", &public_key.to_string()[..8])?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A [`LiteCertificate`] whose signatures are shared, so that cloning it does not copy them.
+#[derive(Clone, Debug)]
+#[cfg_attr(with_testing, derive(Eq, PartialEq))]
+pub struct SharedLiteCertificate {
+    /// Hash and chain ID of the certified value.
+    pub value: LiteValue,
+    /// The round in which the value was certified.
+    pub round: Round,
+    /// Signatures on the value.
+    pub signatures: Arc<[(ValidatorPublicKey, ValidatorSignature)]>,
+}
+
+impl SharedLiteCertificate {
+    /// Returns the [`LiteCertificate`] borrowing the shared signatures.
+    pub fn as_lite(&self) -> LiteCertificate<'_> {
+        LiteCertificate {
+            value: self.value.clone(),
+            round: self.round,
+            signatures: Cow::Borrowed(&self.signatures),
+        }
+    }
+
+    /// Verifies the certificate. See [`LiteCertificate::check`].
+    pub fn check(&self, committee: &Committee) -> Result<&LiteValue, ChainError> {
+        self.as_lite().check(committee)?;
+        Ok(&self.value)
+    }
+
+    /// Returns the public keys of the validators that signed the certificate, in the order
+    /// of `self.signatures`.
+    pub fn signers(&self) -> impl Iterator<Item = &ValidatorPublicKey> {
+        self.signatures.iter().map(|(public_key, _)| public_key)
+    }
+
+    /// Returns the [`GenericCertificate`] with the specified value, if it matches. See
+    /// [`LiteCertificate::with_value`].
+    pub fn with_value<T: CertificateValue>(&self, value: T) -> Option<GenericCertificate<T>> {
+        self.as_lite().with_value(value)
+    }
+}
+
+impl From<LiteCertificate<'_>> for SharedLiteCertificate {
+    fn from(certificate: LiteCertificate<'_>) -> Self {
+        SharedLiteCertificate {
+            value: certificate.value,
+            round: certificate.round,
+            signatures: certificate.signatures.into(),
+        }
+    }
+}
+
+impl TryFrom<Vec<LiteVote>> for LiteCertificate<'static> {
+    type Error = VoteAggregationError;
+
+    /// Aggregates the votes like [`LiteCertificate::try_from_votes_checked`].
+    fn try_from(votes: Vec<LiteVote>) -> Result<Self, Self::Error> {
+        LiteCertificate::try_from_votes_checked(votes)
+    }
+}
+
+impl FromIterator<LiteVote> for Result<LiteCertificate<'static>, VoteAggregationError> {
+    /// Aggregates the votes like [`LiteCertificate::try_from_votes_checked`].
+    fn from_iter<I: IntoIterator<Item = LiteVote>>(votes: I) -> Self {
+        LiteCertificate::try_from_votes_checked(votes)
+    }
+}
+
+impl FromIterator<LiteVote> for Option<LiteCertificate<'static>> {
+    /// Aggregates the votes like [`LiteCertificate::try_from_votes`].
+    fn from_iter<I: IntoIterator<Item = LiteVote>>(votes: I) -> Self {
+        LiteCertificate::try_from_votes(votes)
+    }
+}
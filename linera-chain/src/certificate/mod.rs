@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod confirmed;
+mod equivocation;
 mod generic;
 mod lite;
 mod timeout;
@@ -10,13 +11,17 @@ mod validated;
 
 use std::collections::BTreeSet;
 
+pub use equivocation::{equivocation_proof, partition_votes, EquivocationProof};
 pub use generic::GenericCertificate;
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey, ValidatorSignature},
     data_types::{BlockHeight, Epoch, Round},
     identifiers::{BlobId, ChainId},
 };
-pub use lite::LiteCertificate;
+pub use lite::{
+    dedup_by_value, CommitteeVerifier, LiteCertificate, QuorumMode, SharedLiteCertificate,
+    ValueMatchError, VerifiedCertificateCache, VoteAggregationError, CANONICAL_BYTES_VERSION,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::types::{ConfirmedBlock, Timeout, ValidatedBlock};
@@ -92,6 +97,14 @@ pub enum CertificateKind {
     Confirmed = 2,
 }
 
+impl CertificateKind {
+    /// Returns whether a certificate of this kind can exist in the given round. In
+    /// `Round::Fast`, validators confirm blocks immediately, so there are no validated blocks.
+    pub fn is_valid_in(&self, round: Round) -> bool {
+        !matches!((self, round), (CertificateKind::Validated, Round::Fast))
+    }
+}
+
 pub trait CertificateValue: Clone {
     const KIND: CertificateKind;
 
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{BTreeMap, BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error,
 };
 
@@ -20,6 +20,8 @@ use linera_base::{
     identifiers::{Account, AccountOwner, BlobId, ChainId, MessageId},
 };
 use linera_execution::{committee::Committee, Message, MessageKind, Operation, OutgoingMessage};
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::{iter::ParallelIterator as _, slice::ParallelSlice as _};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -650,6 +652,74 @@ impl<'a, T: CertificateValue> SignatureAggregator<'a, T> {
     }
 }
 
+/// Aggregates votes into a [`LiteCertificate`], verifying each of them as it arrives.
+pub struct LiteSignatureAggregator<'a> {
+    committee: &'a Committee,
+    weight: u64,
+    used_validators: HashSet<ValidatorPublicKey>,
+    value_and_round: Option<(LiteValue, Round)>,
+    signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+}
+
+impl<'a> LiteSignatureAggregator<'a> {
+    /// Starts aggregating votes into a certificate. The value and round of the certificate
+    /// are those of the first accepted vote.
+    pub fn new(committee: &'a Committee) -> Self {
+        Self {
+            committee,
+            weight: 0,
+            used_validators: HashSet::new(),
+            value_and_round: None,
+            signatures: Vec::new(),
+        }
+    }
+
+    /// Returns the total weight of the accepted votes.
+    pub fn weight(&self) -> u64 {
+        self.weight
+    }
+
+    /// Tries to append a vote to the (partial) certificate. Returns `Some(certificate)` when
+    /// a quorum is first reached. Returns an error if the vote is invalid or cannot be
+    /// aggregated with the previous ones.
+    pub fn append(
+        &mut self,
+        vote: LiteVote,
+    ) -> Result<Option<LiteCertificate<'static>>, ChainError> {
+        if let Some((value, round)) = &self.value_and_round {
+            ensure!(
+                *value == vote.value && *round == vote.round,
+                ChainError::VoteMismatch
+            );
+        }
+        vote.check()?;
+        // Check that each validator only appears once.
+        ensure!(
+            !self.used_validators.contains(&vote.public_key),
+            ChainError::CertificateValidatorReuse
+        );
+        // Update weight.
+        let voting_rights = self.committee.weight(&vote.public_key);
+        ensure!(voting_rights > 0, ChainError::InvalidSigner);
+        let reached_quorum = self.weight < self.committee.quorum_threshold()
+            && self.weight + voting_rights >= self.committee.quorum_threshold();
+        self.weight += voting_rights;
+        self.used_validators.insert(vote.public_key);
+        self.signatures.push((vote.public_key, vote.signature));
+        let (value, round) = self.value_and_round.get_or_insert((vote.value, vote.round));
+
+        if reached_quorum {
+            Ok(Some(LiteCertificate::new(
+                value.clone(),
+                *round,
+                self.signatures.clone(),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 // Checks if the array slice is strictly ordered. That means that if the array
 // has duplicates, this will return False, even if the array is sorted
 pub(crate) fn is_strictly_ordered(values: &[(ValidatorPublicKey, ValidatorSignature)]) -> bool {
@@ -657,6 +727,10 @@ pub(crate) fn is_strictly_ordered(values: &[(ValidatorPublicKey, ValidatorSignat
 }
 
 /// Verifies certificate signatures.
+///
+/// Signatures and public keys are public data, so they are compared with the usual
+/// short-circuiting equality: no secret material is involved in these checks. The
+/// verification of each signature is delegated to the signature scheme.
 pub(crate) fn check_signatures(
     value_hash: CryptoHash,
     certificate_kind: CertificateKind,
@@ -664,7 +738,141 @@ pub(crate) fn check_signatures(
     signatures: &[(ValidatorPublicKey, ValidatorSignature)],
     committee: &Committee,
 ) -> Result<(), ChainError> {
-    // Check the quorum.
+    check_signatures_with_index(
+        value_hash,
+        certificate_kind,
+        round,
+        signatures,
+        committee.signer_index(),
+        committee.quorum_threshold(),
+    )
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The number of times certificate signatures were checked on this thread.
+    static SIGNATURE_CHECKS: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
+}
+
+/// Verifies certificate signatures like [`check_signatures`], given the weights of the
+/// committee's validators, indexed by public key, and its quorum threshold.
+pub(crate) fn check_signatures_with_index(
+    value_hash: CryptoHash,
+    certificate_kind: CertificateKind,
+    round: Round,
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    signer_index: &HashMap<ValidatorPublicKey, u64>,
+    quorum_threshold: u64,
+) -> Result<(), ChainError> {
+    #[cfg(test)]
+    SIGNATURE_CHECKS.with(|checks| checks.set(checks.get() + 1));
+    check_quorum(signatures, signer_index, quorum_threshold)?;
+    // All that is left is checking signatures!
+    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
+    ValidatorSignature::verify_batch(&hash_and_round, signatures.iter())?;
+    Ok(())
+}
+
+/// Returns the signers whose signature is invalid or who are not in the committee, in the
+/// order of `signatures`. Contrary to [`check_signatures`], each signature is verified on
+/// its own, so that all the invalid ones are found.
+pub(crate) fn invalid_signers(
+    value_hash: CryptoHash,
+    certificate_kind: CertificateKind,
+    round: Round,
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    committee: &Committee,
+) -> Vec<ValidatorPublicKey> {
+    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
+    signatures
+        .iter()
+        .filter(|(public_key, signature)| {
+            committee.weight(public_key) == 0
+                || signature.check(&hash_and_round, public_key).is_err()
+        })
+        .map(|(public_key, _)| *public_key)
+        .collect()
+}
+
+/// Verifies certificate signatures like [`check_signatures`], verifying the individual
+/// signatures on the threads of the global `rayon` pool where supported. As with
+/// [`check_signatures`], the error of the first invalid signature in the list is reported.
+pub(crate) fn check_signatures_parallel(
+    value_hash: CryptoHash,
+    certificate_kind: CertificateKind,
+    round: Round,
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    committee: &Committee,
+) -> Result<(), ChainError> {
+    check_quorum(
+        signatures,
+        committee.signer_index(),
+        committee.quorum_threshold(),
+    )?;
+    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
+    #[cfg(target_arch = "wasm32")]
+    ValidatorSignature::verify_batch(&hash_and_round, signatures.iter())?;
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        // The chunks are verified on the global thread pool, and their results are
+        // collected in order, so that the first invalid signature is the one reported.
+        let chunk_size = signatures
+            .len()
+            .div_ceil(rayon::current_num_threads())
+            .max(1);
+        signatures
+            .par_chunks(chunk_size)
+            .map(|chunk| ValidatorSignature::verify_batch(&hash_and_round, chunk))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<(), _>>()?;
+    }
+    Ok(())
+}
+
+/// Verifies certificate signatures like [`check_signatures`], but requires the signers to
+/// have a weight of at least `min_weight` instead of a quorum. The weight is only checked
+/// once all signatures have been verified.
+pub(crate) fn check_signatures_with_threshold(
+    value_hash: CryptoHash,
+    certificate_kind: CertificateKind,
+    round: Round,
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    committee: &Committee,
+    min_weight: u64,
+) -> Result<(), ChainError> {
+    let weight = check_signers(signatures, committee.signer_index())?;
+    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
+    ValidatorSignature::verify_batch(&hash_and_round, signatures.iter())?;
+    ensure!(
+        weight >= min_weight,
+        ChainError::InsufficientSignerWeight { weight, min_weight }
+    );
+    Ok(())
+}
+
+/// Checks that the signers are distinct members of the committee and form a quorum. A signer
+/// outside the committee is reported as [`ChainError::UnknownSigner`], before any signature
+/// is verified.
+fn check_quorum(
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    signer_index: &HashMap<ValidatorPublicKey, u64>,
+    quorum_threshold: u64,
+) -> Result<(), ChainError> {
+    let weight = check_signers(signatures, signer_index)?;
+    ensure!(
+        weight >= quorum_threshold,
+        ChainError::CertificateRequiresQuorum
+    );
+    Ok(())
+}
+
+/// Checks that the signers are distinct members of the committee, and returns their total
+/// weight. The signers are looked up in the committee's [`Committee::signer_index`].
+fn check_signers(
+    signatures: &[(ValidatorPublicKey, ValidatorSignature)],
+    signer_index: &HashMap<ValidatorPublicKey, u64>,
+) -> Result<u64, ChainError> {
     let mut weight = 0;
     let mut used_validators = HashSet::new();
     for (validator, _) in signatures {
@@ -674,19 +882,15 @@ pub(crate) fn check_signatures(
             ChainError::CertificateValidatorReuse
         );
         used_validators.insert(*validator);
+        // Check that the validator is a member of the committee.
+        let Some(&voting_rights) = signer_index.get(validator) else {
+            return Err(ChainError::UnknownSigner(*validator));
+        };
         // Update weight.
-        let voting_rights = committee.weight(validator);
         ensure!(voting_rights > 0, ChainError::InvalidSigner);
         weight += voting_rights;
     }
-    ensure!(
-        weight >= committee.quorum_threshold(),
-        ChainError::CertificateRequiresQuorum
-    );
-    // All that is left is checking signatures!
-    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
-    ValidatorSignature::verify_batch(&hash_and_round, signatures.iter())?;
-    Ok(())
+    Ok(weight)
 }
 
 impl BcsSignable<'_> for ProposalContent {}
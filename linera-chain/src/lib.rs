@@ -25,7 +25,7 @@ pub use chain::ChainStateView;
 use data_types::{MessageBundle, PostedMessage};
 use linera_base::{
     bcs,
-    crypto::{CryptoError, CryptoHash},
+    crypto::{CryptoError, CryptoHash, ValidatorPublicKey},
     data_types::{ArithmeticError, BlockHeight, Round, Timestamp},
     identifiers::{ApplicationId, BlobId, ChainId},
 };
@@ -107,6 +107,8 @@ pub enum ChainError {
     },
     #[error("The signature was not created by a valid entity")]
     InvalidSigner,
+    #[error("Validator {0} is not a member of the committee")]
+    UnknownSigner(ValidatorPublicKey),
     #[error(
         "Was expecting block height {expected_block_height} but found {found_block_height} instead"
     )]
@@ -138,8 +140,38 @@ pub enum ChainError {
     CertificateValidatorReuse,
     #[error("Signatures in a certificate must form a quorum")]
     CertificateRequiresQuorum,
+    #[error(
+        "The signers of the certificate have a weight of {weight} instead of at least {min_weight}"
+    )]
+    InsufficientSignerWeight { weight: u64, min_weight: u64 },
     #[error("Certificate signature verification failed: {error}")]
     CertificateSignatureVerificationFailed { error: String },
+    #[error("Only certificates for the same value and round can be merged")]
+    CertificateMergeMismatch,
+    #[error("A certificate of kind {kind:?} cannot be in {round:?}")]
+    InvalidRoundForKind {
+        kind: types::CertificateKind,
+        round: Round,
+    },
+    #[error("Validator {0} provided two different signatures")]
+    ConflictingSignatures(ValidatorPublicKey),
+    #[error(
+        "The votes are not from the same validator, chain, height and round for different values"
+    )]
+    NotAnEquivocation,
+    #[error("The vote is for a different value or round than the previous votes")]
+    VoteMismatch,
+    #[error("Certificate is not valid for any of the committees: {0:?}")]
+    CertificateInvalidForAllCommittees(Vec<ChainError>),
+    #[error("Compact certificate does not match the committee: {0}")]
+    InvalidCompactCertificate(String),
+    #[error("Invalid JSON certificate: {0}")]
+    InvalidCertificateJson(String),
+    #[error("Certificate {index} of the batch is invalid: {error}")]
+    InvalidCertificateInBatch {
+        index: usize,
+        error: Box<ChainError>,
+    },
     #[error("Internal error {0}")]
     InternalError(String),
     #[error("Block proposal is too large")]
@@ -2,14 +2,23 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::borrow::Cow;
+
+use assert_matches::assert_matches;
 use linera_base::{
     crypto::{AccountSecretKey, Ed25519SecretKey, Secp256k1SecretKey, ValidatorKeypair},
-    data_types::Amount,
+    data_types::{Amount, Epoch, RoundKind},
 };
+use linera_execution::{committee::ValidatorState, ResourceControlPolicy};
 
 use super::*;
 use crate::{
     block::{ConfirmedBlock, ValidatedBlock},
+    certificate::{
+        dedup_by_value, equivocation_proof, partition_votes, CommitteeVerifier, EquivocationProof,
+        QuorumMode, SharedLiteCertificate, ValueMatchError, VerifiedCertificateCache,
+        VoteAggregationError, CANONICAL_BYTES_VERSION,
+    },
     test::{make_first_block, BlockTestExt},
 };
 
@@ -17,6 +26,65 @@ fn dummy_chain_id(index: u32) -> ChainId {
     ChainId(CryptoHash::test_hash(format!("chain{}", index)))
 }
 
+fn dummy_confirmed_block() -> ConfirmedBlock {
+    make_confirmed_block(dummy_chain_id(1), dummy_chain_id(1))
+}
+
+/// Returns a confirmed first block of `chain_id`, with a transfer to `recipient`.
+fn make_confirmed_block(chain_id: ChainId, recipient: ChainId) -> ConfirmedBlock {
+    let block = BlockExecutionOutcome {
+        messages: vec![Vec::new()],
+        previous_message_blocks: BTreeMap::new(),
+        state_hash: CryptoHash::test_hash("state"),
+        oracle_responses: vec![Vec::new()],
+        events: vec![Vec::new()],
+        blobs: vec![Vec::new()],
+        operation_results: vec![OperationResult::default()],
+    }
+    .with(make_first_block(chain_id).with_simple_transfer(recipient, Amount::ONE));
+    ConfirmedBlock::new(block)
+}
+
+/// Generates `count` validators and a committee in which each of them has one vote.
+fn make_committee(count: usize) -> (Vec<ValidatorKeypair>, Committee) {
+    make_weighted_committee(&vec![1; count])
+}
+
+/// Generates one validator for each of the given weights, and their committee.
+fn make_weighted_committee(weights: &[u64]) -> (Vec<ValidatorKeypair>, Committee) {
+    let key_pairs = weights
+        .iter()
+        .map(|_| ValidatorKeypair::generate())
+        .collect::<Vec<_>>();
+    let validators = key_pairs
+        .iter()
+        .zip(weights)
+        .map(|(key_pair, votes)| {
+            let account_secret = AccountSecretKey::Ed25519(Ed25519SecretKey::generate());
+            let state = ValidatorState {
+                network_address: key_pair.public_key.to_string(),
+                votes: *votes,
+                account_public_key: account_secret.public(),
+            };
+            (key_pair.public_key, state)
+        })
+        .collect();
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    (key_pairs, committee)
+}
+
+/// Creates a [`LiteCertificate`] for `value`, signed by the given validators.
+fn make_lite_certificate(
+    value: &ConfirmedBlock,
+    round: Round,
+    signers: &[&ValidatorKeypair],
+) -> LiteCertificate<'static> {
+    let votes = signers
+        .iter()
+        .map(|key_pair| LiteVote::new(LiteValue::new(value), round, &key_pair.secret_key));
+    LiteCertificate::try_from_votes(votes).unwrap()
+}
+
 #[test]
 fn test_signed_values() {
     let validator1_key_pair = ValidatorKeypair::generate();
@@ -140,3 +208,1820 @@ fn test_certificates() {
         .is_none());
     assert!(builder.append(v3.public_key, v3.signature).is_err());
 }
+
+#[test]
+fn test_lite_certificate_check_and_signers() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+
+    let signers = [&key_pairs[2], &key_pairs[0], &key_pairs[3]];
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers);
+    let (lite_value, public_keys) = certificate.check_and_signers(&committee).unwrap();
+    assert_eq!(lite_value, certificate.check(&committee).unwrap());
+    let mut expected_keys = signers
+        .iter()
+        .map(|key_pair| key_pair.public_key)
+        .collect::<Vec<_>>();
+    expected_keys.sort();
+    assert_eq!(public_keys, expected_keys);
+    assert!(!public_keys.contains(&key_pairs[1].public_key));
+
+    // Without a quorum, both checks fail in the same way.
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers[..2]);
+    assert_matches!(
+        certificate.check_and_signers(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+}
+
+#[test]
+fn test_lite_certificate_check_batch() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let quorum = [&key_pairs[0], &key_pairs[1], &key_pairs[2]];
+
+    let mut certificates = (0..4)
+        .map(|round| make_lite_certificate(&value, Round::MultiLeader(round), &quorum))
+        .collect::<Vec<_>>();
+    assert!(LiteCertificate::check_batch(&certificates, &committee).is_ok());
+    assert!(LiteCertificate::check_batch(&[], &committee).is_ok());
+
+    // A signature for a different round does not verify.
+    let wrong_signature = make_lite_certificate(&value, Round::Fast, &quorum).signatures;
+    certificates[2].signatures = wrong_signature;
+    assert_matches!(
+        LiteCertificate::check_batch(&certificates, &committee),
+        Err(ChainError::InvalidCertificateInBatch { index: 2, .. })
+    );
+
+    // The first invalid certificate is reported.
+    certificates[1] = make_lite_certificate(&value, Round::Fast, &quorum[..2]);
+    assert_matches!(
+        LiteCertificate::check_batch(&certificates, &committee),
+        Err(ChainError::InvalidCertificateInBatch { index: 1, error })
+            if matches!(*error, ChainError::CertificateRequiresQuorum)
+    );
+}
+
+#[test]
+fn test_lite_certificate_weight() {
+    // The total weight is 10, so the quorum threshold is 7.
+    let (key_pairs, committee) = make_weighted_committee(&[5, 2, 2, 1]);
+    assert_eq!(committee.quorum_threshold(), 7);
+    let value = dummy_confirmed_block();
+    let outsider = ValidatorKeypair::generate();
+
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[3]]);
+    assert_eq!(certificate.weight(&committee), 6);
+    assert!(!certificate.has_quorum(&committee, QuorumMode::Byzantine));
+
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[0], &key_pairs[3], &outsider],
+    );
+    assert_eq!(certificate.weight(&committee), 6);
+    assert!(!certificate.has_quorum(&committee, QuorumMode::Byzantine));
+
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[1]]);
+    assert_eq!(certificate.weight(&committee), 7);
+    assert!(certificate.has_quorum(&committee, QuorumMode::Byzantine));
+
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+    assert_eq!(certificate.weight(&committee), 10);
+    assert!(certificate.has_quorum(&committee, QuorumMode::Byzantine));
+}
+
+#[test]
+fn test_lite_certificate_retain_signers() {
+    // The total weight is 10, so the quorum threshold is 7.
+    let (key_pairs, committee) = make_weighted_committee(&[5, 2, 2, 1]);
+    let value = dummy_confirmed_block();
+    let mut certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+    assert!(certificate.check(&committee).is_ok());
+
+    let group = [key_pairs[0].public_key, key_pairs[2].public_key];
+    certificate.retain_signers(|public_key| group.contains(public_key));
+    let mut expected = group.to_vec();
+    expected.sort();
+    assert_eq!(certificate.signers().copied().collect::<Vec<_>>(), expected);
+    assert_eq!(certificate.weight(&committee), 7);
+    assert!(certificate.has_quorum(&committee, QuorumMode::Byzantine));
+    assert!(certificate.check(&committee).is_ok());
+
+    certificate.retain_signers(|public_key| *public_key != key_pairs[2].public_key);
+    assert_eq!(certificate.weight(&committee), 5);
+    assert!(!certificate.has_quorum(&committee, QuorumMode::Byzantine));
+    assert!(certificate.check(&committee).is_err());
+}
+
+#[test]
+fn test_lite_certificate_quorum_modes() {
+    // The total weight is 10: a majority needs 6, a Byzantine quorum needs 7.
+    let (key_pairs, committee) = make_weighted_committee(&[5, 2, 2, 1]);
+    assert_eq!(QuorumMode::Majority.required_weight(&committee), 6);
+    assert_eq!(QuorumMode::Byzantine.required_weight(&committee), 7);
+    assert_eq!(QuorumMode::default(), QuorumMode::Byzantine);
+    let value = dummy_confirmed_block();
+
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[3]]);
+    assert!(certificate.has_quorum(&committee, QuorumMode::Majority));
+    assert!(!certificate.has_quorum(&committee, QuorumMode::Byzantine));
+    assert!(certificate
+        .check_with_quorum_mode(&committee, QuorumMode::Majority)
+        .is_ok());
+    assert_matches!(
+        certificate.check_with_quorum_mode(&committee, QuorumMode::Byzantine),
+        Err(ChainError::InsufficientSignerWeight {
+            weight: 6,
+            min_weight: 7
+        })
+    );
+
+    // Exactly half of the weight is not a majority.
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0]]);
+    assert!(!certificate.has_quorum(&committee, QuorumMode::Majority));
+    assert_matches!(
+        certificate.check_with_quorum_mode(&committee, QuorumMode::Majority),
+        Err(ChainError::InsufficientSignerWeight {
+            weight: 5,
+            min_weight: 6
+        })
+    );
+}
+
+#[test]
+fn test_lite_certificate_add_verified_vote() {
+    let (key_pairs, committee) = make_weighted_committee(&[4, 3, 2, 1]);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let mut certificate =
+        make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[3]]);
+    assert_eq!(certificate.weight(&committee), 5);
+
+    let vote = LiteVote::new(lite_value.clone(), Round::Fast, &key_pairs[2].secret_key);
+    assert!(certificate.add_verified_vote(vote, &committee).unwrap());
+    assert_eq!(certificate.weight(&committee), 7);
+    assert!(certificate.check(&committee).is_ok());
+    let expected = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[0], &key_pairs[2], &key_pairs[3]],
+    );
+    assert_eq!(certificate, expected);
+
+    let duplicate_vote = LiteVote::new(lite_value.clone(), Round::Fast, &key_pairs[0].secret_key);
+    assert!(!certificate
+        .add_verified_vote(duplicate_vote, &committee)
+        .unwrap());
+    assert_eq!(certificate, expected);
+
+    let other_round_vote = LiteVote::new(
+        lite_value.clone(),
+        Round::MultiLeader(0),
+        &key_pairs[1].secret_key,
+    );
+    assert_matches!(
+        certificate.add_verified_vote(other_round_vote, &committee),
+        Err(ChainError::VoteMismatch)
+    );
+    let mut invalid_vote = LiteVote::new(lite_value.clone(), Round::Fast, &key_pairs[1].secret_key);
+    invalid_vote.signature =
+        LiteVote::new(lite_value.clone(), Round::Fast, &key_pairs[2].secret_key).signature;
+    assert!(certificate
+        .add_verified_vote(invalid_vote, &committee)
+        .is_err());
+    let outsider = ValidatorKeypair::generate();
+    let outsider_vote = LiteVote::new(lite_value.clone(), Round::Fast, &outsider.secret_key);
+    assert_matches!(
+        certificate.add_verified_vote(outsider_vote, &committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider.public_key
+    );
+    assert_eq!(certificate, expected);
+
+    // Duplicates are also detected if the signatures are not sorted.
+    let mut unsorted = expected.clone();
+    unsorted.signatures.to_mut().reverse();
+    let duplicate_vote = LiteVote::new(lite_value.clone(), Round::Fast, &key_pairs[0].secret_key);
+    assert!(!unsorted
+        .add_verified_vote(duplicate_vote, &committee)
+        .unwrap());
+    assert_eq!(unsorted.signature_count(), 3);
+    let vote = LiteVote::new(lite_value, Round::Fast, &key_pairs[1].secret_key);
+    assert!(unsorted.add_verified_vote(vote, &committee).unwrap());
+    assert_eq!(unsorted.weight(&committee), 10);
+    assert_eq!(unsorted.signature_count(), 4);
+}
+
+#[test]
+fn test_lite_certificate_check_with_signer_index() {
+    let (key_pairs, committee) = make_weighted_committee(&[3, 2, 1, 0]);
+    let (_, other_committee) = make_weighted_committee(&[3, 2, 1, 0]);
+    let fresh_committee = committee.clone();
+    let signer_index = committee.signer_index();
+    assert_eq!(signer_index.len(), 4);
+    for key_pair in &key_pairs {
+        assert_eq!(
+            signer_index[&key_pair.public_key],
+            committee.weight(&key_pair.public_key)
+        );
+    }
+    // The index does not affect comparisons.
+    assert_eq!(committee, fresh_committee);
+    assert_ne!(committee, other_committee);
+
+    let value = dummy_confirmed_block();
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[1]]);
+    assert!(certificate.check(&committee).is_ok());
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[3]]);
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::InvalidSigner)
+    );
+    let outsider = ValidatorKeypair::generate();
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &outsider]);
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider.public_key
+    );
+}
+
+#[test]
+fn test_lite_certificate_intersect_signers() {
+    let (key_pairs, _) = make_committee(5);
+    let value = dummy_confirmed_block();
+    let other_value = make_confirmed_block(dummy_chain_id(2), dummy_chain_id(1));
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[0], &key_pairs[1], &key_pairs[2]],
+    );
+    let other_certificate = make_lite_certificate(
+        &other_value,
+        Round::MultiLeader(1),
+        &[&key_pairs[4], &key_pairs[2], &key_pairs[1]],
+    );
+    let public_keys = |indices: &[usize]| {
+        let mut public_keys = indices
+            .iter()
+            .map(|index| key_pairs[*index].public_key)
+            .collect::<Vec<_>>();
+        public_keys.sort();
+        public_keys
+    };
+
+    assert_eq!(
+        certificate.intersect_signers(&other_certificate),
+        public_keys(&[1, 2])
+    );
+    assert_eq!(
+        other_certificate.intersect_signers(&certificate),
+        public_keys(&[1, 2])
+    );
+    assert_eq!(
+        certificate.symmetric_difference_signers(&other_certificate),
+        public_keys(&[0, 4])
+    );
+    assert_eq!(
+        other_certificate.symmetric_difference_signers(&certificate),
+        public_keys(&[0, 4])
+    );
+    assert_eq!(
+        certificate.intersect_signers(&certificate),
+        public_keys(&[0, 1, 2])
+    );
+    assert!(certificate
+        .symmetric_difference_signers(&certificate)
+        .is_empty());
+}
+
+#[test]
+fn test_lite_certificate_display() {
+    let (key_pairs, _) = make_committee(5);
+    let value = dummy_confirmed_block();
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+    let display = certificate.to_string();
+    assert!(display.contains("(5 signers: "));
+    assert!(display.ends_with(", …)"));
+    assert!(display.contains(&format!("{:.8}..", certificate.value.value_hash)));
+    assert!(display.contains(&certificate.value.chain_id.to_string()));
+    assert!(display.contains(&Round::Fast.to_string()));
+    let first_signer = certificate.signatures[0].0.to_string();
+    assert!(display.contains(&format!("{}..", &first_signer[..8])));
+    assert!(!display.contains(&first_signer));
+    assert!(display.len() < format!("{certificate:?}").len());
+
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0]]);
+    let display = certificate.to_string();
+    assert!(display.contains("(1 signers: "));
+    assert!(!display.contains('…'));
+}
+
+#[test]
+fn test_committee_verifier() {
+    let (key_pairs, committee) = make_weighted_committee(&[3, 2, 1, 0]);
+    let (other_key_pairs, other_committee) = make_committee(3);
+    let outsider = ValidatorKeypair::generate();
+    let value = dummy_confirmed_block();
+    let mut invalid = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[1]]);
+    invalid.signatures.to_mut()[0].1 =
+        make_lite_certificate(&value, Round::SingleLeader(1), &[&key_pairs[0]]).signatures[0].1;
+    let certificates = [
+        make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[1]]),
+        make_lite_certificate(&value, Round::Fast, &[&key_pairs[0]]),
+        make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[3]]),
+        make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &outsider]),
+        make_lite_certificate(
+            &value,
+            Round::Fast,
+            &other_key_pairs.iter().collect::<Vec<_>>(),
+        ),
+        invalid,
+    ];
+
+    let mut verifier = CommitteeVerifier::new(Epoch::ZERO, &committee);
+    for certificate in &certificates {
+        assert_eq!(
+            verifier
+                .verify(certificate)
+                .map_err(|error| error.to_string()),
+            certificate
+                .check(&committee)
+                .map_err(|error| error.to_string())
+        );
+    }
+    assert!(verifier.verify(&certificates[0]).is_ok());
+
+    // The verifier is only recreated when the epoch changes.
+    assert!(!verifier.update(Epoch::ZERO, &other_committee));
+    assert!(verifier.verify(&certificates[0]).is_ok());
+    assert!(verifier.update(Epoch::from(1), &other_committee));
+    assert_eq!(verifier.epoch(), Epoch::from(1));
+    for certificate in &certificates {
+        assert_eq!(
+            verifier
+                .verify(certificate)
+                .map_err(|error| error.to_string()),
+            certificate
+                .check(&other_committee)
+                .map_err(|error| error.to_string())
+        );
+    }
+    assert!(verifier.verify(&certificates[4]).is_ok());
+}
+
+#[test]
+fn test_lite_certificate_serialized_size() {
+    let (key_pairs, _) = make_committee(10);
+    let value = dummy_confirmed_block();
+    for signer_count in 1..=10 {
+        let signers = key_pairs.iter().take(signer_count).collect::<Vec<_>>();
+        for round in [Round::Fast, Round::MultiLeader(300), Round::Validator(1)] {
+            let certificate = make_lite_certificate(&value, round, &signers);
+            assert_eq!(
+                certificate.serialized_size(),
+                bcs::to_bytes(&certificate).unwrap().len()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_lite_certificate_signer_bitmap() {
+    let (key_pairs, committee) = make_committee(10);
+    let value = dummy_confirmed_block();
+    let outsider = ValidatorKeypair::generate();
+    let signers = [
+        &key_pairs[1],
+        &key_pairs[4],
+        &key_pairs[5],
+        &key_pairs[9],
+        &outsider,
+    ];
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers);
+
+    let bitmap = certificate.signer_bitmap(&committee);
+    assert_eq!(bitmap.len(), 10);
+    let indices = bitmap
+        .iter()
+        .enumerate()
+        .filter(|(_, signed)| **signed)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    let expected_indices = committee
+        .validators()
+        .keys()
+        .enumerate()
+        .filter(|(_, public_key)| {
+            signers[..4]
+                .iter()
+                .any(|key_pair| key_pair.public_key == **public_key)
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(indices.len(), 4);
+    assert_eq!(indices, expected_indices);
+}
+
+#[test]
+fn test_lite_certificate_invalid_signers() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs.iter().collect::<Vec<_>>();
+    let mut certificate = make_lite_certificate(&value, Round::Fast, &signers);
+    assert!(certificate.check(&committee).is_ok());
+    assert!(certificate.invalid_signers(&committee).is_empty());
+
+    // Replace the signature of one signer with a signature of another round.
+    let other_round = make_lite_certificate(&value, Round::MultiLeader(0), &signers);
+    let tampered = certificate.signatures[2].0;
+    certificate.signatures.to_mut()[2].1 = other_round.signatures[2].1;
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::CryptoError(_))
+    );
+    assert_eq!(certificate.invalid_signers(&committee), vec![tampered]);
+}
+
+#[test]
+fn test_lite_certificate_canonical_bytes_golden_vector() {
+    // The compressed encodings of the generator of secp256k1 and of its double.
+    let public_key1 = ValidatorPublicKey::from_bytes(
+        &hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap(),
+    )
+    .unwrap();
+    let public_key2 = ValidatorPublicKey::from_bytes(
+        &hex::decode("02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5").unwrap(),
+    )
+    .unwrap();
+    let signature = |first: u8, second: u8| {
+        let bytes = [[first; 32], [second; 32]].concat();
+        ValidatorSignature::from_slice(bytes).unwrap()
+    };
+    let value = LiteValue {
+        value_hash: CryptoHash::try_from([1; 32].as_slice()).unwrap(),
+        chain_id: ChainId(CryptoHash::try_from([2; 32].as_slice()).unwrap()),
+        kind: CertificateKind::Confirmed,
+    };
+    // The signatures are deliberately not sorted.
+    let certificate = LiteCertificate {
+        value,
+        round: Round::SingleLeader(7),
+        signatures: Cow::Owned(vec![
+            (public_key2, signature(3, 4)),
+            (public_key1, signature(5, 6)),
+        ]),
+    };
+    let expected = concat!(
+        "01",
+        "0101010101010101010101010101010101010101010101010101010101010101",
+        "0202020202020202020202020202020202020202020202020202020202020202",
+        "02",
+        "0200000007",
+        "00000002",
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        "0505050505050505050505050505050505050505050505050505050505050505",
+        "0606060606060606060606060606060606060606060606060606060606060606",
+        "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5",
+        "0303030303030303030303030303030303030303030303030303030303030303",
+        "0404040404040404040404040404040404040404040404040404040404040404",
+    );
+    assert_eq!(CANONICAL_BYTES_VERSION, 1);
+    assert_eq!(hex::encode(certificate.canonical_bytes()), expected);
+}
+
+#[test]
+fn test_lite_certificate_json_round_trip() {
+    let (key_pairs, _) = make_committee(3);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs.iter().collect::<Vec<_>>();
+    for round in [
+        Round::Fast,
+        Round::MultiLeader(2),
+        Round::SingleLeader(7),
+        Round::Validator(u32::MAX),
+    ] {
+        let mut certificate = make_lite_certificate(&value, round, &signers);
+        // The order of the signatures is preserved, even if it is not sorted.
+        certificate.signatures.to_mut().reverse();
+
+        let json = certificate.to_json();
+        assert_eq!(json["value_hash"], value.hash().to_string());
+        assert_eq!(json["round"], round.to_string());
+        let (public_key, signature) = &certificate.signatures[0];
+        assert_eq!(json["signatures"][0]["validator"], public_key.to_string());
+        assert_eq!(json["signatures"][0]["signature"], signature.to_string());
+
+        let decoded = LiteCertificate::from_json(&json).unwrap();
+        assert_eq!(decoded, certificate);
+        assert_eq!(
+            bcs::to_bytes(&decoded).unwrap(),
+            bcs::to_bytes(&certificate).unwrap()
+        );
+        assert_eq!(decoded.to_json(), json);
+    }
+
+    let mut json = make_lite_certificate(&value, Round::Fast, &signers).to_json();
+    json["round"] = "round 3".into();
+    assert_matches!(
+        LiteCertificate::from_json(&json),
+        Err(ChainError::InvalidCertificateJson(_))
+    );
+}
+
+#[test]
+fn test_shared_lite_certificate_clones_share_signatures() {
+    let (key_pairs, committee) = make_committee(100);
+    let value = dummy_confirmed_block();
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+    let shared = SharedLiteCertificate::from(certificate.clone());
+
+    let clones = (0..1000).map(|_| shared.clone()).collect::<Vec<_>>();
+    for clone in &clones {
+        assert!(std::sync::Arc::ptr_eq(
+            &clone.signatures,
+            &shared.signatures
+        ));
+    }
+    assert_eq!(std::sync::Arc::strong_count(&shared.signatures), 1001);
+
+    let clone = &clones[0];
+    assert_eq!(clone.as_lite(), certificate);
+    assert!(clone.check(&committee).is_ok());
+    assert!(clone.signers().eq(certificate.signers()));
+    let full_certificate = clone.with_value(value.clone()).unwrap();
+    assert_eq!(full_certificate.value(), &value);
+    assert!(clone
+        .with_value(make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2)))
+        .is_none());
+}
+
+#[test]
+fn test_lite_certificate_merge() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let round = Round::MultiLeader(1);
+
+    let certificate1 = make_lite_certificate(&value, round, &[&key_pairs[3], &key_pairs[1]]);
+    let certificate2 = make_lite_certificate(&value, round, &[&key_pairs[0], &key_pairs[1]]);
+    assert!(certificate1.check(&committee).is_err());
+    assert!(certificate2.check(&committee).is_err());
+    let merged = certificate1.clone().merge(certificate2.clone()).unwrap();
+    assert!(merged.check(&committee).is_ok());
+    let mut expected_keys = [0, 1, 3].map(|index| key_pairs[index].public_key);
+    expected_keys.sort();
+    let merged_keys = merged
+        .signatures
+        .iter()
+        .map(|(public_key, _)| *public_key)
+        .collect::<Vec<_>>();
+    assert_eq!(merged_keys, expected_keys);
+    assert_eq!(merged, certificate2.merge(certificate1.clone()).unwrap());
+
+    // Certificates for different rounds cannot be merged.
+    let other_round = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0]]);
+    assert_matches!(
+        certificate1.clone().merge(other_round),
+        Err(ChainError::CertificateMergeMismatch)
+    );
+
+    // Certificates for different values cannot be merged.
+    let mut other_value = make_lite_certificate(&value, round, &[&key_pairs[0]]);
+    other_value.value.value_hash = CryptoHash::test_hash("other value");
+    assert_matches!(
+        certificate1.clone().merge(other_value),
+        Err(ChainError::CertificateMergeMismatch)
+    );
+
+    // A validator cannot contribute two different signatures.
+    let mut conflicting = make_lite_certificate(&value, round, &[&key_pairs[1]]);
+    let other_signature = make_lite_certificate(&value, Round::Fast, &[&key_pairs[1]]);
+    conflicting.signatures = other_signature.signatures;
+    let conflicting_key = key_pairs[1].public_key;
+    assert_matches!(
+        certificate1.merge(conflicting),
+        Err(ChainError::ConflictingSignatures(public_key)) if public_key == conflicting_key
+    );
+}
+
+#[test]
+fn test_dedup_by_value() {
+    let (key_pairs, committee) = make_committee(4);
+    let value1 = dummy_confirmed_block();
+    let value2 = make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2));
+    let round = Round::MultiLeader(1);
+    let certificate = |value, round, signers: &[usize]| {
+        let signers = signers.iter().map(|index| &key_pairs[*index]);
+        make_lite_certificate(value, round, &signers.collect::<Vec<_>>())
+    };
+    let certificates = vec![
+        certificate(&value1, round, &[0, 1]),
+        certificate(&value2, round, &[2]),
+        certificate(&value1, round, &[1, 3]),
+        certificate(&value1, Round::Fast, &[0]),
+        certificate(&value2, round, &[0, 2]),
+        certificate(&value1, round, &[3]),
+    ];
+    let deduplicated = dedup_by_value(certificates, &committee);
+
+    let signers =
+        |certificate: &LiteCertificate| -> Vec<_> { certificate.signers().copied().collect() };
+    let expected_signers = |indices: &[usize]| {
+        let mut public_keys = indices
+            .iter()
+            .map(|index| key_pairs[*index].public_key)
+            .collect::<Vec<_>>();
+        public_keys.sort();
+        public_keys
+    };
+    assert_eq!(deduplicated.len(), 3);
+    assert_eq!(deduplicated[0].value, LiteValue::new(&value1));
+    assert_eq!(deduplicated[0].round, round);
+    assert_eq!(signers(&deduplicated[0]), expected_signers(&[0, 1, 3]));
+    assert_eq!(deduplicated[1].value, LiteValue::new(&value2));
+    assert_eq!(deduplicated[1].round, round);
+    assert_eq!(signers(&deduplicated[1]), expected_signers(&[0, 2]));
+    assert_eq!(deduplicated[2].value, LiteValue::new(&value1));
+    assert_eq!(deduplicated[2].round, Round::Fast);
+    assert_eq!(signers(&deduplicated[2]), expected_signers(&[0]));
+    assert!(deduplicated[0].check(&committee).is_ok());
+    assert!(deduplicated[1].check(&committee).is_err());
+
+    // A conflicting signature is taken from the certificate with the highest weight.
+    let mut conflicting = certificate(&value1, round, &[1]);
+    conflicting.signatures = certificate(&value1, Round::Fast, &[1]).signatures;
+    let heavy = certificate(&value1, round, &[0, 1, 2]);
+    let deduplicated = dedup_by_value(vec![conflicting, heavy.clone()], &committee);
+    assert_eq!(deduplicated, vec![heavy.clone()]);
+
+    // Validated and confirmed certificates for the same block have the same value hash, but
+    // their signatures are not interchangeable, so they are kept apart.
+    let validated_value = LiteValue {
+        kind: CertificateKind::Validated,
+        ..heavy.value.clone()
+    };
+    let validated = LiteCertificate::try_from_votes([0, 1, 3].iter().map(|index| {
+        LiteVote::new(
+            validated_value.clone(),
+            round,
+            &key_pairs[*index].secret_key,
+        )
+    }))
+    .unwrap();
+    let deduplicated = dedup_by_value(vec![heavy.clone(), validated.clone()], &committee);
+    assert_eq!(deduplicated, vec![heavy, validated]);
+    assert!(deduplicated[0].check(&committee).is_ok());
+    assert!(deduplicated[1].check(&committee).is_ok());
+}
+
+#[test]
+fn test_lite_certificate_try_new_rejects_duplicates() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let round = Round::Fast;
+    let vote = |index: usize, round| {
+        LiteVote::new(lite_value.clone(), round, &key_pairs[index].secret_key)
+    };
+    let signature = |vote: LiteVote| (vote.public_key, vote.signature);
+
+    // Identical signatures are kept once, and the result is sorted by validator.
+    let signatures = vec![
+        signature(vote(2, round)),
+        signature(vote(0, round)),
+        signature(vote(2, round)),
+        signature(vote(1, round)),
+        signature(vote(0, round)),
+    ];
+    let certificate = LiteCertificate::try_new(lite_value.clone(), round, signatures).unwrap();
+    let mut expected_keys = [0, 1, 2].map(|index| key_pairs[index].public_key);
+    expected_keys.sort();
+    let keys = certificate
+        .signatures
+        .iter()
+        .map(|(public_key, _)| *public_key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, expected_keys);
+    assert_eq!(certificate.weight(&committee), 3);
+    assert!(certificate.check(&committee).is_ok());
+
+    // Two different signatures from the same validator are rejected.
+    let signatures = vec![
+        signature(vote(0, round)),
+        signature(vote(1, round)),
+        (
+            key_pairs[0].public_key,
+            vote(0, Round::MultiLeader(0)).signature,
+        ),
+    ];
+    let conflicting_key = key_pairs[0].public_key;
+    assert_matches!(
+        LiteCertificate::try_new(lite_value.clone(), round, signatures),
+        Err(ChainError::ConflictingSignatures(public_key)) if public_key == conflicting_key
+    );
+    let votes = [vote(0, round), vote(1, round), vote(0, round)];
+    assert!(LiteCertificate::try_from_votes(votes).is_some());
+}
+
+#[test]
+fn test_lite_certificate_check_against_any() {
+    let (old_key_pairs, old_committee) = make_committee(4);
+    let (new_key_pairs, new_committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&new_key_pairs[0], &new_key_pairs[1], &new_key_pairs[2]],
+    );
+    let (index, lite_value) = certificate
+        .check_against_any(&[&old_committee, &new_committee])
+        .unwrap();
+    assert_eq!(index, 1);
+    assert_eq!(*lite_value, LiteValue::new(&value));
+
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&old_key_pairs[0], &old_key_pairs[1], &new_key_pairs[2]],
+    );
+    assert_matches!(
+        certificate.check_against_any(&[&old_committee, &new_committee]),
+        Err(ChainError::CertificateInvalidForAllCommittees(errors)) if matches!(
+            errors.as_slice(),
+            [ChainError::UnknownSigner(_), ChainError::UnknownSigner(_)]
+        )
+    );
+    assert_matches!(
+        certificate.check_against_any(&[]),
+        Err(ChainError::CertificateInvalidForAllCommittees(errors)) if errors.is_empty()
+    );
+}
+
+#[test]
+fn test_lite_signature_aggregator() {
+    let (key_pairs, committee) = make_weighted_committee(&[3, 2, 2, 2]);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let vote = |index: usize, round| {
+        LiteVote::new(lite_value.clone(), round, &key_pairs[index].secret_key)
+    };
+
+    let mut aggregator = LiteSignatureAggregator::new(&committee);
+    assert!(aggregator.append(vote(1, Round::Fast)).unwrap().is_none());
+    assert_matches!(
+        aggregator.append(vote(1, Round::Fast)),
+        Err(ChainError::CertificateValidatorReuse)
+    );
+
+    // Votes for another round or value are rejected.
+    assert_matches!(
+        aggregator.append(vote(0, Round::MultiLeader(0))),
+        Err(ChainError::VoteMismatch)
+    );
+    let mut other_value = vote(0, Round::Fast);
+    other_value.value.value_hash = CryptoHash::test_hash("other value");
+    assert_matches!(
+        aggregator.append(other_value),
+        Err(ChainError::VoteMismatch)
+    );
+
+    // Invalid signatures and signers outside the committee are rejected.
+    let mut forged = vote(0, Round::Fast);
+    forged.signature = vote(2, Round::Fast).signature;
+    assert_matches!(aggregator.append(forged), Err(ChainError::CryptoError(_)));
+    let outsider = ValidatorKeypair::generate();
+    let outsider_vote = LiteVote::new(lite_value.clone(), Round::Fast, &outsider.secret_key);
+    assert_matches!(
+        aggregator.append(outsider_vote),
+        Err(ChainError::InvalidSigner)
+    );
+    assert_eq!(aggregator.weight(), 2);
+
+    // The certificate is returned exactly when the quorum is first reached.
+    assert!(aggregator.append(vote(2, Round::Fast)).unwrap().is_none());
+    let certificate = aggregator.append(vote(0, Round::Fast)).unwrap().unwrap();
+    assert!(certificate.check(&committee).is_ok());
+    assert_eq!(certificate.value, lite_value);
+    assert_eq!(certificate.signatures.len(), 3);
+    assert!(aggregator.append(vote(3, Round::Fast)).unwrap().is_none());
+    assert_eq!(aggregator.weight(), 9);
+}
+
+#[test]
+fn test_equivocation_proof() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let mut other_lite_value = lite_value.clone();
+    other_lite_value.value_hash = CryptoHash::test_hash("other value");
+    let secret_key = &key_pairs[0].secret_key;
+    let round = Round::MultiLeader(2);
+    let height = BlockHeight(0);
+
+    let vote = LiteVote::new(lite_value.clone(), round, secret_key);
+    let other_vote = LiteVote::new(other_lite_value.clone(), round, secret_key);
+    let proof = equivocation_proof((height, vote.clone()), (height, other_vote.clone())).unwrap();
+    assert_eq!(proof.validator(), key_pairs[0].public_key);
+    assert_eq!(proof.verify(&committee).unwrap(), key_pairs[0].public_key);
+
+    // The proof is only valid for members of the committee, with valid signatures.
+    let (_, other_committee) = make_committee(4);
+    assert_matches!(
+        proof.verify(&other_committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == key_pairs[0].public_key
+    );
+    let mut forged = proof.clone();
+    forged.second.signature = vote.signature;
+    assert_matches!(forged.verify(&committee), Err(ChainError::CryptoError(_)));
+    let mut forged = proof;
+    forged.second = vote.clone();
+    assert_matches!(
+        forged.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+
+    // Votes for the same value, in different rounds or from different validators do not conflict.
+    assert!(equivocation_proof((height, vote.clone()), (height, vote.clone())).is_none());
+    let later_vote = LiteVote::new(other_lite_value.clone(), Round::MultiLeader(3), secret_key);
+    assert!(equivocation_proof((height, vote.clone()), (height, later_vote)).is_none());
+    let other_validator_vote =
+        LiteVote::new(other_lite_value.clone(), round, &key_pairs[1].secret_key);
+    assert!(equivocation_proof((height, vote.clone()), (height, other_validator_vote)).is_none());
+
+    // Rounds are only meaningful for a given chain and height: votes for blocks at different
+    // heights or of different chains do not conflict, and neither do votes of different kinds.
+    let next_height = BlockHeight(1);
+    assert!(equivocation_proof((height, vote.clone()), (next_height, other_vote)).is_none());
+    let mut other_chain_value = other_lite_value.clone();
+    other_chain_value.chain_id = dummy_chain_id(2);
+    let other_chain_vote = LiteVote::new(other_chain_value, round, secret_key);
+    assert!(equivocation_proof((height, vote.clone()), (height, other_chain_vote)).is_none());
+    let mut validated_value = other_lite_value;
+    validated_value.kind = CertificateKind::Validated;
+    let validated_vote = LiteVote::new(validated_value, round, secret_key);
+    assert!(equivocation_proof((height, vote), (height, validated_vote)).is_none());
+}
+
+#[test]
+fn test_equivocation_proof_verify() {
+    let (key_pairs, committee) = make_committee(4);
+    let lite_value = LiteValue::new(&dummy_confirmed_block());
+    let mut other_lite_value = lite_value.clone();
+    other_lite_value.value_hash = CryptoHash::test_hash("other value");
+    let round = Round::SingleLeader(1);
+    let vote = |value: &LiteValue, round, index: usize| {
+        LiteVote::new(value.clone(), round, &key_pairs[index].secret_key)
+    };
+    let height = BlockHeight(3);
+    let proof = |first, second| EquivocationProof {
+        first_height: height,
+        first,
+        second_height: height,
+        second,
+    };
+
+    let valid = proof(
+        vote(&lite_value, round, 2),
+        vote(&other_lite_value, round, 2),
+    );
+    assert_eq!(valid.verify(&committee).unwrap(), key_pairs[2].public_key);
+
+    // The votes must be from the same validator.
+    let different_signers = proof(
+        vote(&lite_value, round, 2),
+        vote(&other_lite_value, round, 3),
+    );
+    assert_matches!(
+        different_signers.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+
+    // The validator must be a member of the committee.
+    let outsider = ValidatorKeypair::generate();
+    let outsider_proof = proof(
+        LiteVote::new(lite_value.clone(), round, &outsider.secret_key),
+        LiteVote::new(other_lite_value.clone(), round, &outsider.secret_key),
+    );
+    assert_matches!(
+        outsider_proof.verify(&committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider.public_key
+    );
+
+    // The votes must be in the same round.
+    let rounds_differ = proof(
+        vote(&lite_value, round, 2),
+        vote(&other_lite_value, Round::SingleLeader(2), 2),
+    );
+    assert_matches!(
+        rounds_differ.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+
+    // The votes must be for the same chain and height: an honest validator signs blocks of
+    // several chains, and consecutive blocks of a chain, in the fast round.
+    let mut other_chain_value = other_lite_value.clone();
+    other_chain_value.chain_id = dummy_chain_id(2);
+    let chains_differ = proof(
+        vote(&lite_value, Round::Fast, 2),
+        vote(&other_chain_value, Round::Fast, 2),
+    );
+    assert_matches!(
+        chains_differ.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+    let mut heights_differ = proof(
+        vote(&lite_value, Round::Fast, 2),
+        vote(&other_lite_value, Round::Fast, 2),
+    );
+    assert!(heights_differ.verify(&committee).is_ok());
+    heights_differ.second_height = BlockHeight(4);
+    assert_matches!(
+        heights_differ.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+
+    // The votes must be for different values.
+    let same_value = proof(vote(&lite_value, round, 2), vote(&lite_value, round, 2));
+    assert_matches!(
+        same_value.verify(&committee),
+        Err(ChainError::NotAnEquivocation)
+    );
+
+    // Both signatures must be valid.
+    let mut bad_first_signature = valid.clone();
+    bad_first_signature.first.signature = vote(&lite_value, round, 1).signature;
+    assert_matches!(
+        bad_first_signature.verify(&committee),
+        Err(ChainError::CryptoError(_))
+    );
+    let mut bad_second_signature = valid;
+    bad_second_signature.second.signature = vote(&other_lite_value, Round::Fast, 2).signature;
+    assert_matches!(
+        bad_second_signature.verify(&committee),
+        Err(ChainError::CryptoError(_))
+    );
+}
+
+#[test]
+fn test_lite_certificate_trim_to_quorum() {
+    // The total weight is 15, so the quorum threshold is 11.
+    let (key_pairs, committee) = make_weighted_committee(&[1, 5, 2, 4, 3]);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs.iter().collect::<Vec<_>>();
+    let mut certificate = make_lite_certificate(&value, Round::Fast, &signers);
+
+    certificate.trim_to_quorum(&committee);
+    assert!(certificate.check(&committee).is_ok());
+    assert_eq!(certificate.weight(&committee), 12);
+    let mut expected_keys = [1, 3, 4].map(|index| key_pairs[index].public_key);
+    expected_keys.sort();
+    let keys = certificate
+        .signatures
+        .iter()
+        .map(|(public_key, _)| *public_key)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, expected_keys);
+    for index in 0..certificate.signatures.len() {
+        let mut smaller = certificate.clone();
+        smaller.signatures.to_mut().remove(index);
+        assert_matches!(
+            smaller.check(&committee),
+            Err(ChainError::CertificateRequiresQuorum)
+        );
+    }
+
+    // Without a quorum, nothing is removed.
+    let mut certificate = make_lite_certificate(&value, Round::Fast, &signers[..3]);
+    certificate.trim_to_quorum(&committee);
+    assert_eq!(certificate.signatures.len(), 3);
+}
+
+#[test]
+fn test_generic_certificate_lite_round_trip() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let round = Round::MultiLeader(3);
+    let mut aggregator = SignatureAggregator::new(value.clone(), round, &committee);
+    let mut certificate = None;
+    for key_pair in &key_pairs[..3] {
+        let vote = LiteVote::new(LiteValue::new(&value), round, &key_pair.secret_key);
+        certificate = aggregator.append(vote.public_key, vote.signature).unwrap();
+    }
+    let certificate = certificate.unwrap();
+
+    let lite_certificate = certificate.lite_certificate();
+    assert_matches!(lite_certificate.signatures, Cow::Borrowed(_));
+    assert_eq!(lite_certificate.round, round);
+    assert_eq!(lite_certificate.value, LiteValue::new(&value));
+    assert!(lite_certificate.check(&committee).is_ok());
+    assert!(lite_certificate.check_value(&value));
+    let full_certificate = lite_certificate.cloned().with_value(value).unwrap();
+    assert_eq!(full_certificate, certificate);
+}
+
+#[test]
+fn test_lite_certificate_try_from_votes_checked() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let mut other_lite_value = lite_value.clone();
+    other_lite_value.value_hash = CryptoHash::test_hash("other value");
+    let vote = |index: usize, value: &LiteValue, round| {
+        LiteVote::new(value.clone(), round, &key_pairs[index].secret_key)
+    };
+
+    let votes = (0..3).map(|index| vote(index, &lite_value, Round::Fast));
+    let certificate = LiteCertificate::try_from_votes_checked(votes).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked([]),
+        Err(VoteAggregationError::Empty)
+    );
+    let votes = [
+        vote(0, &lite_value, Round::Fast),
+        vote(1, &other_lite_value, Round::Fast),
+    ];
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes.clone()),
+        Err(VoteAggregationError::ValueMismatch { expected, found })
+            if expected == lite_value.value_hash && found == other_lite_value.value_hash
+    );
+    assert!(LiteCertificate::try_from_votes(votes).is_none());
+    let votes = [
+        vote(0, &lite_value, Round::Fast),
+        vote(1, &lite_value, Round::MultiLeader(0)),
+    ];
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes),
+        Err(VoteAggregationError::RoundMismatch {
+            expected: Round::Fast,
+            found: Round::MultiLeader(0),
+        })
+    );
+    let mut conflicting_vote = vote(0, &lite_value, Round::Fast);
+    conflicting_vote.signature = vote(0, &other_lite_value, Round::Fast).signature;
+    let votes = [vote(0, &lite_value, Round::Fast), conflicting_vote];
+    let conflicting_key = key_pairs[0].public_key;
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes),
+        Err(VoteAggregationError::ConflictingSignatures(public_key))
+            if public_key == conflicting_key
+    );
+}
+
+#[test]
+fn test_lite_certificate_try_from_votes_weighted() {
+    // The total weight is 10, so the quorum threshold is 7.
+    let (key_pairs, committee) = make_weighted_committee(&[4, 2, 2, 1, 1]);
+    let lite_value = LiteValue::new(&dummy_confirmed_block());
+    let vote = |index: usize| {
+        LiteVote::new(
+            lite_value.clone(),
+            Round::Fast,
+            &key_pairs[index].secret_key,
+        )
+    };
+    let outsider = ValidatorKeypair::generate();
+
+    // Just below the quorum, even with a repeated vote and a signer outside the committee.
+    let votes = [
+        vote(0),
+        vote(1),
+        vote(1),
+        LiteVote::new(lite_value.clone(), Round::Fast, &outsider.secret_key),
+    ];
+    assert!(LiteCertificate::try_from_votes_weighted(votes, &committee).is_none());
+    let votes = [vote(0), vote(3), vote(4)];
+    assert!(LiteCertificate::try_from_votes_weighted(votes, &committee).is_none());
+
+    // Just at the quorum: the remaining votes are not used.
+    let votes = [vote(0), vote(1), vote(3), vote(4), vote(2)];
+    let certificate = LiteCertificate::try_from_votes_weighted(votes, &committee).unwrap();
+    assert_eq!(certificate.weight(&committee), 7);
+    assert_eq!(certificate.signature_count(), 3);
+    assert!(certificate.check(&committee).is_ok());
+
+    // Votes that cannot be aggregated give no certificate.
+    let mut other_value = lite_value.clone();
+    other_value.value_hash = CryptoHash::test_hash("other value");
+    let votes = [
+        vote(0),
+        LiteVote::new(other_value, Round::Fast, &key_pairs[1].secret_key),
+        vote(3),
+    ];
+    assert!(LiteCertificate::try_from_votes_weighted(votes, &committee).is_none());
+}
+
+#[test]
+fn test_lite_certificate_try_from_votes_with_other_chain_or_kind() {
+    let (key_pairs, _) = make_committee(4);
+    let lite_value = LiteValue::new(&dummy_confirmed_block());
+    let mut other_chain_value = lite_value.clone();
+    other_chain_value.chain_id = dummy_chain_id(2);
+    let mut other_kind_value = lite_value.clone();
+    other_kind_value.kind = CertificateKind::Validated;
+    let vote = |index: usize, value: &LiteValue| {
+        LiteVote::new(value.clone(), Round::Fast, &key_pairs[index].secret_key)
+    };
+
+    // The votes share the value hash, but not the chain.
+    let votes = [vote(0, &lite_value), vote(1, &other_chain_value)];
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes.clone()),
+        Err(VoteAggregationError::ChainIdMismatch { expected, found })
+            if expected == lite_value.chain_id && found == other_chain_value.chain_id
+    );
+    assert!(LiteCertificate::try_from_votes(votes).is_none());
+
+    let votes = [vote(0, &lite_value), vote(1, &other_kind_value)];
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes.clone()),
+        Err(VoteAggregationError::KindMismatch {
+            expected: CertificateKind::Confirmed,
+            found: CertificateKind::Validated,
+        })
+    );
+    assert!(LiteCertificate::try_from_votes(votes).is_none());
+}
+
+#[test]
+fn test_lite_certificate_try_from_vec_of_votes() {
+    let (key_pairs, committee) = make_committee(4);
+    let lite_value = LiteValue::new(&dummy_confirmed_block());
+    let mut other_lite_value = lite_value.clone();
+    other_lite_value.value_hash = CryptoHash::test_hash("other value");
+    let votes = key_pairs
+        .iter()
+        .take(3)
+        .map(|key_pair| LiteVote::new(lite_value.clone(), Round::Fast, &key_pair.secret_key))
+        .collect::<Vec<_>>();
+
+    let certificate = LiteCertificate::try_from(votes.clone()).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+    let collected = votes.iter().cloned().collect::<Option<LiteCertificate>>();
+    assert_eq!(collected, Some(certificate.clone()));
+    let collected = votes
+        .iter()
+        .cloned()
+        .collect::<Result<LiteCertificate, VoteAggregationError>>();
+    assert_eq!(collected.unwrap(), certificate);
+
+    assert_matches!(
+        LiteCertificate::try_from(Vec::new()),
+        Err(VoteAggregationError::Empty)
+    );
+    let mut mismatched_votes = votes;
+    mismatched_votes.push(LiteVote::new(
+        other_lite_value.clone(),
+        Round::Fast,
+        &key_pairs[3].secret_key,
+    ));
+    assert!(mismatched_votes
+        .iter()
+        .cloned()
+        .collect::<Option<LiteCertificate>>()
+        .is_none());
+    assert_matches!(
+        LiteCertificate::try_from(mismatched_votes),
+        Err(VoteAggregationError::ValueMismatch { expected, found })
+            if expected == lite_value.value_hash && found == other_lite_value.value_hash
+    );
+}
+
+#[test]
+fn test_lite_certificate_try_new_rejects_invalid_rounds() {
+    let (key_pairs, committee) = make_committee(4);
+    let confirmed_value = dummy_confirmed_block();
+    let validated_value = ValidatedBlock::new(confirmed_value.block().clone());
+    let sign = |value: &LiteValue, round| {
+        key_pairs
+            .iter()
+            .map(|key_pair| {
+                let vote = LiteVote::new(value.clone(), round, &key_pair.secret_key);
+                (vote.public_key, vote.signature)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lite_value = LiteValue::new(&confirmed_value);
+    let signatures = sign(&lite_value, Round::Fast);
+    let certificate = LiteCertificate::try_new(lite_value, Round::Fast, signatures).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+
+    let lite_value = LiteValue::new(&validated_value);
+    let signatures = sign(&lite_value, Round::MultiLeader(0));
+    let certificate =
+        LiteCertificate::try_new(lite_value.clone(), Round::MultiLeader(0), signatures).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+
+    let signatures = sign(&lite_value, Round::Fast);
+    assert_matches!(
+        LiteCertificate::try_new(lite_value.clone(), Round::Fast, signatures),
+        Err(ChainError::InvalidRoundForKind {
+            kind: CertificateKind::Validated,
+            round: Round::Fast,
+        })
+    );
+    let votes = key_pairs
+        .iter()
+        .map(|key_pair| LiteVote::new(lite_value.clone(), Round::Fast, &key_pair.secret_key));
+    assert_matches!(
+        LiteCertificate::try_from_votes_checked(votes),
+        Err(VoteAggregationError::InvalidRoundForKind {
+            kind: CertificateKind::Validated,
+            round: Round::Fast,
+        })
+    );
+}
+
+#[test]
+fn test_lite_certificate_round_kind() {
+    let (key_pairs, _) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs.iter().collect::<Vec<_>>();
+    for (round, kind) in [
+        (Round::Fast, RoundKind::Fast),
+        (Round::MultiLeader(0), RoundKind::MultiLeader),
+        (Round::MultiLeader(3), RoundKind::MultiLeader),
+        (Round::SingleLeader(0), RoundKind::SingleLeader),
+        (Round::SingleLeader(5), RoundKind::SingleLeader),
+        (Round::Validator(0), RoundKind::Validator),
+        (Round::Validator(2), RoundKind::Validator),
+    ] {
+        let certificate = make_lite_certificate(&value, round, &signers);
+        assert_eq!(certificate.round_kind(), kind);
+        assert_eq!(certificate.is_fast(), kind == RoundKind::Fast);
+        assert_eq!(
+            certificate.is_multi_leader(),
+            kind == RoundKind::MultiLeader
+        );
+    }
+}
+
+#[test]
+fn test_lite_certificate_from_sorted_slice() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let owned = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[0], &key_pairs[1], &key_pairs[2]],
+    );
+
+    let signatures = owned.signatures.to_vec();
+    let borrowed =
+        LiteCertificate::from_sorted_slice(owned.value.clone(), owned.round, &signatures);
+    assert_matches!(borrowed.signatures, Cow::Borrowed(_));
+    assert_eq!(borrowed, owned);
+    assert_eq!(
+        borrowed.check(&committee).unwrap(),
+        owned.check(&committee).unwrap()
+    );
+    let short_signatures = &signatures[..2];
+    let borrowed_short =
+        LiteCertificate::from_sorted_slice(owned.value.clone(), owned.round, short_signatures);
+    assert_matches!(
+        borrowed_short.check(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+
+    let cloned: LiteCertificate<'static> = borrowed.cloned();
+    assert_matches!(cloned.signatures, Cow::Owned(_));
+    assert_eq!(cloned, owned);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "signatures must be sorted and deduplicated")]
+fn test_lite_certificate_from_unsorted_slice() {
+    let (key_pairs, _) = make_committee(2);
+    let value = dummy_confirmed_block();
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &key_pairs[1]]);
+    let mut signatures = certificate.signatures.to_vec();
+    signatures.reverse();
+    LiteCertificate::from_sorted_slice(certificate.value, certificate.round, &signatures);
+}
+
+#[test]
+fn test_lite_certificate_compact_encoding() {
+    let (key_pairs, committee) = make_committee(50);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs
+        .iter()
+        .step_by(3)
+        .chain(key_pairs.iter().skip(1).step_by(2))
+        .collect::<Vec<_>>();
+    let certificate = make_lite_certificate(&value, Round::MultiLeader(4), &signers);
+    assert!(certificate.check(&committee).is_ok());
+
+    let compact = certificate.to_compact(&committee).unwrap();
+    let decoded = LiteCertificate::from_compact(&compact, &committee).unwrap();
+    assert_eq!(decoded, certificate);
+    assert!(decoded.check(&committee).is_ok());
+    let bcs_bytes = bcs::to_bytes(&certificate).unwrap();
+    assert!(compact.len() < bcs_bytes.len());
+
+    // Signers must belong to the committee.
+    let outsider = ValidatorKeypair::generate();
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0], &outsider]);
+    assert_matches!(
+        certificate.to_compact(&committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider.public_key
+    );
+
+    // The encoding is only valid for the same committee.
+    let (_, other_committee) = make_committee(8);
+    assert_matches!(
+        LiteCertificate::from_compact(&compact, &other_committee),
+        Err(ChainError::InvalidCompactCertificate(_))
+    );
+}
+
+#[test]
+fn test_lite_certificate_signers() {
+    let (key_pairs, _) = make_committee(5);
+    let value = dummy_confirmed_block();
+    let votes = [3, 0, 4].map(|index| {
+        LiteVote::new(
+            LiteValue::new(&value),
+            Round::Fast,
+            &key_pairs[index].secret_key,
+        )
+    });
+    let certificate = LiteCertificate::try_from_votes(votes).unwrap();
+
+    let mut expected_keys = [3, 0, 4].map(|index| key_pairs[index].public_key);
+    expected_keys.sort();
+    assert!(certificate.signers().eq(expected_keys.iter()));
+    assert_eq!(certificate.signature_count(), 3);
+    assert_eq!(certificate.signers().count(), certificate.signature_count());
+}
+
+#[test]
+fn test_lite_certificate_check_cached() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let signers = [&key_pairs[0], &key_pairs[1], &key_pairs[2]];
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers);
+    let mut cache = VerifiedCertificateCache::new();
+    let signature_checks = || SIGNATURE_CHECKS.with(|checks| checks.get());
+    let initial_checks = signature_checks();
+
+    let lite_value = certificate
+        .check_cached(Epoch::ZERO, &committee, &mut cache)
+        .unwrap();
+    assert_eq!(*lite_value, LiteValue::new(&value));
+    assert_eq!(signature_checks() - initial_checks, 1);
+    let lite_value = certificate
+        .check_cached(Epoch::ZERO, &committee, &mut cache)
+        .unwrap();
+    assert_eq!(*lite_value, LiteValue::new(&value));
+    assert_eq!(signature_checks() - initial_checks, 1);
+    assert_eq!(cache.len(), 1);
+
+    // A certificate for the same value and round with other signatures is verified again.
+    let mut forged = certificate.clone();
+    forged.signatures.to_mut()[0].1 = certificate.signatures[1].1;
+    assert!(forged
+        .check_cached(Epoch::ZERO, &committee, &mut cache)
+        .is_err());
+    assert!(forged
+        .check_cached(Epoch::ZERO, &committee, &mut cache)
+        .is_err());
+    assert_eq!(signature_checks() - initial_checks, 3);
+
+    // Changing the epoch invalidates the cache.
+    let (_, other_committee) = make_committee(4);
+    assert!(certificate
+        .check_cached(Epoch(1), &other_committee, &mut cache)
+        .is_err());
+    assert!(cache.is_empty());
+    assert!(certificate
+        .check_cached(Epoch::ZERO, &committee, &mut cache)
+        .is_ok());
+    assert_eq!(signature_checks() - initial_checks, 5);
+}
+
+#[test]
+fn test_lite_certificate_check_parallel() {
+    let (key_pairs, committee) = make_committee(100);
+    let value = dummy_confirmed_block();
+    let signers = key_pairs.iter().take(80).collect::<Vec<_>>();
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers);
+    let results_match = |certificate: &LiteCertificate| {
+        format!("{:?}", certificate.check(&committee))
+            == format!("{:?}", certificate.check_parallel(&committee))
+    };
+    assert!(certificate.check_parallel(&committee).is_ok());
+    assert!(results_match(&certificate));
+
+    // With several invalid signatures, the same one is reported.
+    let mut tampered = certificate.clone();
+    let other_round = make_lite_certificate(&value, Round::MultiLeader(0), &signers);
+    for index in [75, 12, 40] {
+        tampered.signatures.to_mut()[index].1 = other_round.signatures[index].1;
+        assert!(tampered.check_parallel(&committee).is_err());
+        assert!(results_match(&tampered));
+    }
+
+    let mut tampered = certificate.clone();
+    tampered.signatures.to_mut().truncate(60);
+    assert_matches!(
+        tampered.check_parallel(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+    assert!(results_match(&tampered));
+    let mut tampered = certificate;
+    let duplicate = tampered.signatures[3];
+    tampered.signatures.to_mut()[4] = duplicate;
+    assert_matches!(
+        tampered.check_parallel(&committee),
+        Err(ChainError::CertificateValidatorReuse)
+    );
+    assert!(results_match(&tampered));
+}
+
+#[test]
+fn test_lite_certificate_getters() {
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let round = Round::SingleLeader(7);
+    let certificate = LiteCertificate::new(lite_value.clone(), round, Vec::new());
+    assert_eq!(certificate.value_hash(), value.hash());
+    assert_eq!(certificate.chain_id(), dummy_chain_id(1));
+    assert_eq!(certificate.kind(), CertificateKind::Confirmed);
+    assert_eq!(certificate.round(), round);
+}
+
+#[test]
+fn test_lite_certificate_check_rejects_unknown_signer() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let outsider = ValidatorKeypair::generate();
+    let signers = [&key_pairs[0], &key_pairs[1], &key_pairs[2], &outsider];
+    let certificate = make_lite_certificate(&value, Round::Fast, &signers);
+    let outsider_key = outsider.public_key;
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider_key
+    );
+    assert_matches!(
+        certificate.check_parallel(&committee),
+        Err(ChainError::UnknownSigner(public_key)) if public_key == outsider_key
+    );
+}
+
+#[test]
+fn test_lite_certificate_supersedes() {
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+    let rounds = [
+        Round::Fast,
+        Round::MultiLeader(0),
+        Round::MultiLeader(3),
+        Round::SingleLeader(0),
+        Round::SingleLeader(2),
+        Round::Validator(0),
+        Round::Validator(1),
+    ];
+    let certificates =
+        rounds.map(|round| LiteCertificate::new(lite_value.clone(), round, Vec::new()));
+    for (certificate, round) in certificates.iter().zip(rounds) {
+        for (other, other_round) in certificates.iter().zip(rounds) {
+            assert_eq!(certificate.supersedes(other), round > other_round);
+            assert!(certificate.same_value(other));
+        }
+    }
+
+    // Certificates for other chains never supersede each other.
+    let mut other_chain_value = lite_value.clone();
+    other_chain_value.chain_id = dummy_chain_id(2);
+    let other_chain = LiteCertificate::new(other_chain_value, Round::Validator(5), Vec::new());
+    assert!(!other_chain.supersedes(&certificates[0]));
+    assert!(!certificates[6].supersedes(&other_chain));
+    assert!(!other_chain.same_value(&certificates[0]));
+
+    let mut other_value = lite_value;
+    other_value.value_hash = CryptoHash::test_hash("other value");
+    let other_value = LiteCertificate::new(other_value, Round::Fast, Vec::new());
+    assert!(!other_value.same_value(&certificates[0]));
+    assert!(certificates[1].supersedes(&other_value));
+}
+
+#[test]
+fn test_lite_certificate_try_from_votes_until_quorum() {
+    let (key_pairs, committee) = make_committee(7);
+    let value = dummy_confirmed_block();
+    let lite_value = LiteValue::new(&value);
+
+    // An endless stream of votes is only consumed until the quorum of 5 votes is reached.
+    let mut pulled = 0;
+    let votes = (0..).map(|index: usize| {
+        pulled += 1;
+        let key_pair = &key_pairs[index % key_pairs.len()];
+        LiteVote::new(lite_value.clone(), Round::Fast, &key_pair.secret_key)
+    });
+    let certificate = LiteCertificate::try_from_votes_until_quorum(votes, &committee).unwrap();
+    assert_eq!(pulled, 5);
+    assert_eq!(certificate.signature_count(), 5);
+    assert!(certificate.check(&committee).is_ok());
+
+    // A vote with an invalid signature is rejected.
+    let mut votes = key_pairs
+        .iter()
+        .map(|key_pair| LiteVote::new(lite_value.clone(), Round::Fast, &key_pair.secret_key))
+        .collect::<Vec<_>>();
+    votes[2].signature = votes[1].signature;
+    assert_matches!(
+        LiteCertificate::try_from_votes_until_quorum(votes.clone(), &committee),
+        Err(ChainError::CryptoError(_))
+    );
+
+    // Without enough votes, there is no certificate.
+    assert_matches!(
+        LiteCertificate::try_from_votes_until_quorum(votes.into_iter().take(2), &committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+}
+
+#[test]
+fn test_lite_certificate_with_value_checked() {
+    let (key_pairs, _) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+
+    let other_chain_value = make_confirmed_block(dummy_chain_id(2), dummy_chain_id(1));
+    assert_matches!(
+        certificate.clone().with_value_checked(other_chain_value.clone()),
+        Err(ValueMatchError::ChainIdMismatch { expected, found })
+            if expected == dummy_chain_id(1) && found == dummy_chain_id(2)
+    );
+    assert!(certificate.clone().with_value(other_chain_value).is_none());
+
+    let validated_value = ValidatedBlock::new(value.block().clone());
+    assert_matches!(
+        certificate.clone().with_value_checked(validated_value),
+        Err(ValueMatchError::KindMismatch {
+            expected: CertificateKind::Confirmed,
+            found: CertificateKind::Validated,
+        })
+    );
+
+    let other_value = make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2));
+    assert_matches!(
+        certificate.clone().with_value_checked(other_value.clone()),
+        Err(ValueMatchError::HashMismatch { expected, found })
+            if expected == value.hash() && found == other_value.hash()
+    );
+    assert!(!certificate.check_value(&other_value));
+
+    let full_certificate = certificate.with_value_checked(value.clone()).unwrap();
+    assert_eq!(full_certificate.value(), &value);
+}
+
+#[test]
+fn test_generic_certificate_check_agrees_with_lite_certificate() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    for signer_count in 1..=4 {
+        let signers = key_pairs.iter().take(signer_count).collect::<Vec<_>>();
+        let certificate = make_lite_certificate(&value, Round::Fast, &signers)
+            .with_value(value.clone())
+            .unwrap();
+        let result = certificate.check(&committee);
+        let lite_result = certificate.lite_certificate().check(&committee);
+        assert_eq!(result.is_ok(), signer_count >= 3);
+        match (result, lite_result) {
+            (Ok(checked_value), Ok(lite_value)) => {
+                assert_eq!(checked_value, &value);
+                assert_eq!(lite_value, &LiteValue::new(&value));
+            }
+            (Err(error), Err(lite_error)) => {
+                assert_eq!(format!("{error:?}"), format!("{lite_error:?}"))
+            }
+            (result, lite_result) => panic!("{result:?} disagrees with {lite_result:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_lite_certificate_check_value_agrees_with_with_value() {
+    let (key_pairs, _) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+
+    assert!(certificate.check_value(&value));
+    assert!(certificate.clone().with_value(value.clone()).is_some());
+
+    // Mismatching chain ID, then mismatching hash.
+    for other_value in [
+        make_confirmed_block(dummy_chain_id(2), dummy_chain_id(1)),
+        make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2)),
+    ] {
+        assert!(!certificate.check_value(&other_value));
+        assert!(certificate.clone().with_value(other_value).is_none());
+    }
+
+    // Mismatching kind.
+    let validated_value = ValidatedBlock::new(value.block().clone());
+    assert!(!certificate.check_value(&validated_value));
+    assert!(certificate.with_value(validated_value).is_none());
+}
+
+#[test]
+fn test_lite_certificate_check_with_threshold() {
+    // The quorum threshold is 7, out of a total weight of 10.
+    let (key_pairs, committee) = make_weighted_committee(&[4, 3, 2, 1]);
+    let value = dummy_confirmed_block();
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[0], &key_pairs[1], &key_pairs[3]],
+    );
+    assert!(certificate.check(&committee).is_ok());
+    assert!(certificate.check_with_threshold(&committee, 7).is_ok());
+    assert!(certificate.check_with_threshold(&committee, 8).is_ok());
+    assert_matches!(
+        certificate.check_with_threshold(&committee, 9),
+        Err(ChainError::InsufficientSignerWeight {
+            weight: 8,
+            min_weight: 9
+        })
+    );
+
+    // The threshold also applies below the quorum.
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[2]]);
+    assert_matches!(
+        certificate.check(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+    assert!(certificate.check_with_threshold(&committee, 2).is_ok());
+    assert!(certificate.check_with_threshold(&committee, 1).is_ok());
+    assert_matches!(
+        certificate.check_with_threshold(&committee, 3),
+        Err(ChainError::InsufficientSignerWeight { .. })
+    );
+
+    // Invalid signatures are reported even if the threshold is not met.
+    let mut certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[0]]);
+    let other_value = make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2));
+    let other_vote = LiteVote::new(
+        LiteValue::new(&other_value),
+        Round::Fast,
+        &key_pairs[0].secret_key,
+    );
+    certificate.signatures.to_mut()[0].1 = other_vote.signature;
+    assert_matches!(
+        certificate.check_with_threshold(&committee, 5),
+        Err(ChainError::CryptoError(_))
+    );
+}
+
+#[test]
+fn test_partition_votes() {
+    let (key_pairs, _) = make_committee(4);
+    let value = LiteValue::new(&dummy_confirmed_block());
+    let other_value = LiteValue::new(&make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2)));
+    let vote = |value: &LiteValue, round, index: usize| {
+        LiteVote::new(value.clone(), round, &key_pairs[index].secret_key)
+    };
+    let votes = vec![
+        vote(&value, Round::Fast, 0),
+        vote(&value, Round::Fast, 1),
+        vote(&other_value, Round::Fast, 2),
+        // Validator 1 equivocates.
+        vote(&other_value, Round::Fast, 1),
+        // Validator 0 repeats its vote.
+        vote(&value, Round::Fast, 0),
+        // Validator 2 votes for another value in a different round.
+        vote(&value, Round::SingleLeader(1), 2),
+        vote(&value, Round::Fast, 1),
+        // Validator 3 equivocates in a later round.
+        vote(&value, Round::SingleLeader(1), 3),
+        vote(&other_value, Round::SingleLeader(1), 3),
+        vote(&value, Round::SingleLeader(1), 3),
+    ];
+    let height = BlockHeight(0);
+    let (consistent, conflicts) = partition_votes(votes.iter().map(|vote| (height, vote.clone())));
+    assert_eq!(
+        consistent,
+        vec![
+            (height, votes[0].clone()),
+            (height, votes[2].clone()),
+            (height, votes[5].clone())
+        ]
+    );
+    assert_eq!(
+        conflicts,
+        vec![
+            (
+                key_pairs[1].public_key,
+                [votes[1].clone(), votes[3].clone()]
+            ),
+            (
+                key_pairs[3].public_key,
+                [votes[7].clone(), votes[8].clone()]
+            ),
+        ]
+    );
+    for (_, [first, second]) in conflicts {
+        assert!(equivocation_proof((height, first), (height, second)).is_some());
+    }
+
+    // Votes for other chains or heights, or of another kind, are not compared: validator 0
+    // voting on two chains, at two heights, and for the validated and the confirmed block
+    // does not equivocate.
+    let other_chain_value =
+        LiteValue::new(&make_confirmed_block(dummy_chain_id(2), dummy_chain_id(1)));
+    let mut validated_value = value.clone();
+    validated_value.kind = CertificateKind::Validated;
+    let votes = vec![
+        (height, vote(&value, Round::Fast, 0)),
+        (height, vote(&other_chain_value, Round::Fast, 0)),
+        (BlockHeight(1), vote(&other_value, Round::Fast, 0)),
+        (height, vote(&validated_value, Round::Fast, 0)),
+    ];
+    let (consistent, conflicts) = partition_votes(votes.clone());
+    assert_eq!(consistent, votes);
+    assert!(conflicts.is_empty());
+}
+
+#[test]
+fn test_lite_certificate_check_rejects_tampered_signatures() {
+    let (key_pairs, committee) = make_committee(4);
+    let value = dummy_confirmed_block();
+    let other_value = LiteValue::new(&make_confirmed_block(dummy_chain_id(1), dummy_chain_id(2)));
+    let certificate =
+        make_lite_certificate(&value, Round::Fast, &key_pairs.iter().collect::<Vec<_>>());
+    assert!(certificate.check(&committee).is_ok());
+    // Whichever signature is tampered with, the certificate is rejected with the same error.
+    for position in 0..certificate.signatures.len() {
+        let mut tampered = certificate.clone();
+        let (public_key, signature) = &mut tampered.signatures.to_mut()[position];
+        let key_pair = key_pairs
+            .iter()
+            .find(|key_pair| key_pair.public_key == *public_key)
+            .unwrap();
+        *signature =
+            LiteVote::new(other_value.clone(), Round::Fast, &key_pair.secret_key).signature;
+        assert_matches!(
+            tampered.check(&committee),
+            Err(ChainError::CryptoError(
+                CryptoError::InvalidSignature { .. }
+            ))
+        );
+        assert_matches!(
+            tampered.check_parallel(&committee),
+            Err(ChainError::CryptoError(
+                CryptoError::InvalidSignature { .. }
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_lite_certificate_contributing_validators() {
+    let (key_pairs, committee) = make_weighted_committee(&[5, 1, 3, 2]);
+    let value = dummy_confirmed_block();
+    let certificate = make_lite_certificate(
+        &value,
+        Round::Fast,
+        &[&key_pairs[3], &key_pairs[0], &key_pairs[2]],
+    );
+    let validators = certificate.contributing_validators(&committee).unwrap();
+    let mut expected = [3, 0, 2]
+        .into_iter()
+        .map(|index| {
+            (
+                key_pairs[index].public_key,
+                committee.weight(&key_pairs[index].public_key),
+            )
+        })
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(validators, expected);
+    assert_eq!(
+        validators.iter().map(|(_, weight)| weight).sum::<u64>(),
+        certificate.weight(&committee)
+    );
+    assert_eq!(certificate.weight(&committee), 10);
+
+    // Nothing is returned for an invalid certificate.
+    let certificate = make_lite_certificate(&value, Round::Fast, &[&key_pairs[1], &key_pairs[3]]);
+    assert_matches!(
+        certificate.contributing_validators(&committee),
+        Err(ChainError::CertificateRequiresQuorum)
+    );
+}
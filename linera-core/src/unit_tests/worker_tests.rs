@@ -3632,7 +3632,7 @@ where
         .unwrap()
         .get(&response.info.epoch.unwrap())
         .unwrap()
-        .validators
+        .validators()
         .get(&vote.public_key)
         .unwrap()
         .account_public_key;
@@ -0,0 +1,82 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the cost of looking up the signers of a certificate in a large committee, in
+//! the map of validators and in the committee's signer index.
+
+use std::collections::BTreeMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use linera_base::crypto::{
+    AccountSecretKey, Ed25519SecretKey, ValidatorKeypair, ValidatorPublicKey,
+};
+use linera_execution::{
+    committee::{Committee, ValidatorState},
+    ResourceControlPolicy,
+};
+
+/// The number of validators in the committee.
+const VALIDATOR_COUNT: usize = 1000;
+
+/// Returns a committee of `VALIDATOR_COUNT` validators, and the public keys of a quorum
+/// of them.
+fn make_committee() -> (Committee, Vec<ValidatorPublicKey>) {
+    let account_public_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate()).public();
+    let validators = (0..VALIDATOR_COUNT)
+        .map(|_| {
+            let public_key = ValidatorKeypair::generate().public_key;
+            let state = ValidatorState {
+                network_address: public_key.to_string(),
+                votes: 1,
+                account_public_key,
+            };
+            (public_key, state)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    let quorum = usize::try_from(committee.quorum_threshold()).unwrap();
+    let signers = committee
+        .validators()
+        .keys()
+        .take(quorum)
+        .copied()
+        .collect();
+    (committee, signers)
+}
+
+/// Looks up each signer in the map of validators, as certificate checks used to do.
+fn validators_lookup(committee: &Committee, signers: &[ValidatorPublicKey]) -> u64 {
+    signers
+        .iter()
+        .filter(|signer| committee.validators().contains_key(*signer))
+        .map(|signer| committee.weight(signer))
+        .sum()
+}
+
+/// Looks up each signer in the signer index of the committee.
+fn signer_index_lookup(committee: &Committee, signers: &[ValidatorPublicKey]) -> u64 {
+    let signer_index = committee.signer_index();
+    signers
+        .iter()
+        .filter_map(|signer| signer_index.get(signer))
+        .sum()
+}
+
+fn committee_lookup_benchmark(c: &mut Criterion) {
+    let (committee, signers) = make_committee();
+    assert_eq!(
+        validators_lookup(&committee, &signers),
+        signer_index_lookup(&committee, &signers)
+    );
+    let mut group = c.benchmark_group("committee_lookup");
+    group.bench_function("validators", |bencher| {
+        bencher.iter(|| validators_lookup(black_box(&committee), black_box(&signers)))
+    });
+    group.bench_function("signer_index", |bencher| {
+        bencher.iter(|| signer_index_lookup(black_box(&committee), black_box(&signers)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, committee_lookup_benchmark);
+criterion_main!(benches);
@@ -2,7 +2,13 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::OnceLock,
+};
 
 use async_graphql::InputObject;
 use linera_base::crypto::{AccountPublicKey, CryptoError, ValidatorPublicKey};
@@ -61,8 +67,9 @@ pub struct ValidatorState {
 /// A set of validators (identified by their public keys) and their voting rights.
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Default, InputObject)]
 pub struct Committee {
-    /// The validators in the committee.
-    pub validators: BTreeMap<ValidatorPublicKey, ValidatorState>,
+    /// The validators in the committee. They cannot be modified, so that the
+    /// [`Committee::signer_index`] derived from them stays valid.
+    validators: BTreeMap<ValidatorPublicKey, ValidatorState>,
     /// The sum of all voting rights.
     total_votes: u64,
     /// The threshold to form a quorum.
@@ -71,6 +78,26 @@ pub struct Committee {
     validity_threshold: u64,
     /// The policy agreed on for this epoch.
     policy: ResourceControlPolicy,
+    /// The weights of the validators, indexed by public key. See [`Committee::signer_index`].
+    #[graphql(skip)]
+    signer_index: SignerIndex,
+}
+
+/// The lazily computed weights of the validators of a committee. It is derived from the
+/// validators, so it is ignored when comparing or hashing committees.
+#[derive(Clone, Debug, Default)]
+struct SignerIndex(OnceLock<HashMap<ValidatorPublicKey, u64>>);
+
+impl PartialEq for SignerIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for SignerIndex {}
+
+impl Hash for SignerIndex {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
 }
 
 impl Serialize for Committee {
@@ -159,6 +186,7 @@ impl<'a> From<&'a Committee> for CommitteeFull<'a> {
             quorum_threshold,
             validity_threshold,
             policy,
+            signer_index: _,
         } = committee;
         CommitteeFull {
             validators: Cow::Borrowed(validators),
@@ -185,6 +213,7 @@ impl<'a> From<&'a Committee> for CommitteeMinimal<'a> {
             quorum_threshold: _,
             validity_threshold: _,
             policy,
+            signer_index: _,
         } = committee;
         CommitteeMinimal {
             validators: Cow::Borrowed(validators),
@@ -232,6 +261,7 @@ impl Committee {
             quorum_threshold,
             validity_threshold,
             policy,
+            signer_index: SignerIndex::default(),
         }
     }
 
@@ -260,6 +290,15 @@ impl Committee {
         }
     }
 
+    /// Returns the weights of the validators, indexed by public key, to look up many signers
+    /// in constant time. The index is computed on first use and kept with the committee,
+    /// whose validators never change.
+    pub fn signer_index(&self) -> &HashMap<ValidatorPublicKey, u64> {
+        self.signer_index
+            .0
+            .get_or_init(|| self.keys_and_weights().collect())
+    }
+
     pub fn keys_and_weights(&self) -> impl Iterator<Item = (ValidatorPublicKey, u64)> + '_ {
         self.validators
             .iter()
@@ -0,0 +1,235 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measures the throughput of the hashes of the views, and of the available hashers on the
+//! same amount of data. The views use an in-memory context and are not saved, so that the
+//! measurements reflect the hashing and not the storage. Feeding the hashers with a
+//! [`BlockBufferedHasher`] is also compared with feeding them each serialized value.
+
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId,
+    Criterion, Throughput,
+};
+use linera_views::{
+    collection_view::CollectionView,
+    context::MemoryContext,
+    log_view::LogView,
+    map_view::MapView,
+    register_view::RegisterView,
+    views::{BlockBufferedHasher, HashableView, Hasher, View, Xxh3Hasher},
+};
+use tokio::runtime::Runtime;
+
+/// The sizes of the registers, in bytes.
+const REGISTER_SIZES: [usize; 3] = [32, 1024, 65536];
+
+/// The numbers of entries of the logs, maps and collections.
+const ENTRY_COUNTS: [usize; 3] = [10, 100, 1000];
+
+/// The numbers of 100-byte entries of the logs used to compare the ways to feed the hashers,
+/// up to about 4 MB.
+const LARGE_ENTRY_COUNTS: [usize; 2] = [4_000, 40_000];
+
+fn hash_bytes<H: Hasher>(bytes: &[u8]) -> H::Output {
+    let mut hasher = H::default();
+    hasher.update_with_bytes(bytes).unwrap();
+    hasher.finalize()
+}
+
+/// Benchmarks the hashers on `bytes`, the serialization of the contents of a view.
+fn bench_hashers(group: &mut BenchmarkGroup<WallTime>, size: usize, bytes: &[u8]) {
+    group.bench_with_input(
+        BenchmarkId::new("sha3_256", size),
+        bytes,
+        |bencher, bytes| bencher.iter(|| hash_bytes::<sha3::Sha3_256>(black_box(bytes))),
+    );
+    group.bench_with_input(BenchmarkId::new("sha256", size), bytes, |bencher, bytes| {
+        bencher.iter(|| hash_bytes::<sha2::Sha256>(black_box(bytes)))
+    });
+    group.bench_with_input(
+        BenchmarkId::new("keccak256", size),
+        bytes,
+        |bencher, bytes| bencher.iter(|| hash_bytes::<sha3::Keccak256>(black_box(bytes))),
+    );
+    group.bench_with_input(BenchmarkId::new("blake3", size), bytes, |bencher, bytes| {
+        bencher.iter(|| hash_bytes::<blake3::Hasher>(black_box(bytes)))
+    });
+    group.bench_with_input(BenchmarkId::new("xxh3", size), bytes, |bencher, bytes| {
+        bencher.iter(|| hash_bytes::<Xxh3Hasher>(black_box(bytes)))
+    });
+}
+
+/// Benchmarks the hash of `view`, whose contents serialize to `bytes`, and the hashers on
+/// the same bytes.
+fn bench_view<V>(
+    group: &mut BenchmarkGroup<WallTime>,
+    runtime: &Runtime,
+    size: usize,
+    view: &V,
+    bytes: &[u8],
+) where
+    V: HashableView<MemoryContext<()>> + Sync,
+{
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function(BenchmarkId::new("view", size), |bencher| {
+        bencher
+            .to_async(runtime)
+            .iter(|| async move { black_box(view.hash().await.unwrap()) })
+    });
+    bench_hashers(group, size, bytes);
+}
+
+fn bench_register_view(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let mut group = criterion.benchmark_group("register_view_hash");
+    for size in REGISTER_SIZES {
+        let value = vec![0xab_u8; size];
+        let view = runtime.block_on(async {
+            let context = MemoryContext::new_for_testing(());
+            let mut view = RegisterView::<_, Vec<u8>>::load(context).await.unwrap();
+            view.set(value.clone());
+            view
+        });
+        let bytes = bcs::to_bytes(&value).unwrap();
+        bench_view(&mut group, &runtime, size, &view, &bytes);
+    }
+    group.finish();
+}
+
+fn bench_log_view(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let mut group = criterion.benchmark_group("log_view_hash");
+    for count in ENTRY_COUNTS {
+        let values = (0..count as u64).collect::<Vec<_>>();
+        let view = runtime.block_on(async {
+            let context = MemoryContext::new_for_testing(());
+            let mut view = LogView::<_, u64>::load(context).await.unwrap();
+            for value in &values {
+                view.push(*value);
+            }
+            view
+        });
+        let bytes = bcs::to_bytes(&values).unwrap();
+        bench_view(&mut group, &runtime, count, &view, &bytes);
+    }
+    group.finish();
+}
+
+fn bench_map_view(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let mut group = criterion.benchmark_group("map_view_hash");
+    for count in ENTRY_COUNTS {
+        let entries = (0..count as u64)
+            .map(|index| (index, index.to_string()))
+            .collect::<Vec<_>>();
+        let view = runtime.block_on(async {
+            let context = MemoryContext::new_for_testing(());
+            let mut view = MapView::<_, u64, String>::load(context).await.unwrap();
+            for (index, value) in &entries {
+                view.insert(index, value.clone()).unwrap();
+            }
+            view
+        });
+        let bytes = bcs::to_bytes(&entries).unwrap();
+        bench_view(&mut group, &runtime, count, &view, &bytes);
+    }
+    group.finish();
+}
+
+fn bench_collection_view(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let mut group = criterion.benchmark_group("collection_view_hash");
+    for count in ENTRY_COUNTS {
+        let entries = (0..count as u64)
+            .map(|index| (index, index.to_string()))
+            .collect::<Vec<_>>();
+        let view = runtime.block_on(async {
+            let context = MemoryContext::new_for_testing(());
+            let mut view = CollectionView::<_, u64, RegisterView<_, String>>::load(context)
+                .await
+                .unwrap();
+            for (index, value) in &entries {
+                view.load_entry_mut(index).await.unwrap().set(value.clone());
+            }
+            view
+        });
+        let bytes = bcs::to_bytes(&entries).unwrap();
+        bench_view(&mut group, &runtime, count, &view, &bytes);
+    }
+    group.finish();
+}
+
+fn hash_values<H: Hasher>(values: &[Vec<u8>]) -> H::Output {
+    let mut hasher = H::default();
+    for value in values {
+        hasher.update_with_bcs_bytes(value).unwrap();
+    }
+    hasher.finalize()
+}
+
+fn hash_values_by_blocks<H: Hasher>(values: &[Vec<u8>]) -> H::Output {
+    let mut writer = BlockBufferedHasher::new(H::default());
+    for value in values {
+        bcs::serialize_into(&mut writer, value).unwrap();
+    }
+    writer.into_inner().unwrap().finalize()
+}
+
+/// Benchmarks feeding the hashers with each value of a large log, and with whole blocks.
+fn bench_block_buffered_feeding<H: Hasher>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    count: usize,
+    values: &[Vec<u8>],
+) {
+    group.bench_with_input(
+        BenchmarkId::new(format!("{name}_per_value"), count),
+        values,
+        |bencher, values| bencher.iter(|| hash_values::<H>(black_box(values))),
+    );
+    group.bench_with_input(
+        BenchmarkId::new(format!("{name}_by_blocks"), count),
+        values,
+        |bencher, values| bencher.iter(|| hash_values_by_blocks::<H>(black_box(values))),
+    );
+}
+
+fn bench_large_log_view(criterion: &mut Criterion) {
+    let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+    let mut group = criterion.benchmark_group("large_log_view_hash");
+    group.sample_size(20);
+    for count in LARGE_ENTRY_COUNTS {
+        let values = (0..count)
+            .map(|index| vec![index as u8; 100])
+            .collect::<Vec<_>>();
+        let view = runtime.block_on(async {
+            let context = MemoryContext::new_for_testing(());
+            let mut view = LogView::<_, Vec<u8>>::load(context).await.unwrap();
+            for value in &values {
+                view.push(value.clone());
+            }
+            view
+        });
+        let bytes = bcs::to_bytes(&values).unwrap();
+        group.throughput(Throughput::Bytes(bytes.len() as u64));
+        let view = &view;
+        group.bench_function(BenchmarkId::new("view", count), |bencher| {
+            bencher
+                .to_async(&runtime)
+                .iter(|| async move { black_box(view.hash().await.unwrap()) })
+        });
+        bench_block_buffered_feeding::<sha3::Sha3_256>(&mut group, "sha3_256", count, &values);
+        bench_block_buffered_feeding::<blake3::Hasher>(&mut group, "blake3", count, &values);
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_register_view,
+    bench_log_view,
+    bench_map_view,
+    bench_collection_view,
+    bench_large_log_view
+);
+criterion_main!(benches);
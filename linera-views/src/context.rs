@@ -107,6 +107,18 @@ pub trait Context: Clone {
     /// Mutable getter for the address of the base key.
     fn base_key_mut(&mut self) -> &mut BaseKey;
 
+    /// The key mixed into the hashes of the views using this context, if any. Views with
+    /// the same contents have unrelated hashes under different keys.
+    fn hash_key(&self) -> Option<&[u8; 32]> {
+        None
+    }
+
+    /// Whether the empty maps, sets, logs, queues and collections using this context hash to
+    /// [`crate::views::EMPTY_SENTINEL_HASH`] instead of the hash of their empty contents.
+    fn empty_sentinel(&self) -> bool {
+        false
+    }
+
     /// Obtains a similar [`Context`] implementation with a different base key.
     fn clone_with_base_key(&self, base_key: Vec<u8>) -> Self {
         let mut context = self.clone();
@@ -125,6 +137,10 @@ pub struct ViewContext<E, S> {
     base_key: BaseKey,
     /// User-defined data attached to the view.
     extra: E,
+    /// The key mixed into the hashes of the views, if any.
+    hash_key: Option<[u8; 32]>,
+    /// Whether the empty collection views hash to a sentinel.
+    empty_sentinel: bool,
 }
 
 impl<E, S> ViewContext<E, S>
@@ -149,8 +165,24 @@ impl<E, S> ViewContext<E, S> {
             store,
             base_key: BaseKey { bytes: base_key },
             extra,
+            hash_key: None,
+            empty_sentinel: false,
         }
     }
+
+    /// Returns this context with the given key mixed into the hashes of its views. See
+    /// [`Context::hash_key`].
+    pub fn with_hash_key(mut self, hash_key: [u8; 32]) -> Self {
+        self.hash_key = Some(hash_key);
+        self
+    }
+
+    /// Returns this context with the empty collection views hashing to a sentinel. See
+    /// [`Context::empty_sentinel`].
+    pub fn with_empty_sentinel(mut self) -> Self {
+        self.empty_sentinel = true;
+        self
+    }
 }
 
 impl<E, S> Context for ViewContext<E, S>
@@ -179,6 +211,14 @@ where
     fn base_key_mut(&mut self) -> &mut BaseKey {
         &mut self.base_key
     }
+
+    fn hash_key(&self) -> Option<&[u8; 32]> {
+        self.hash_key.as_ref()
+    }
+
+    fn empty_sentinel(&self) -> bool {
+        self.empty_sentinel
+    }
 }
 
 /// An implementation of [`crate::context::Context`] that stores all values in memory.
@@ -196,6 +236,8 @@ impl<E> MemoryContext<E> {
             .unwrap(),
             base_key: BaseKey::default(),
             extra,
+            hash_key: None,
+            empty_sentinel: false,
         }
     }
 }
@@ -20,7 +20,10 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        empty_sentinel_hash, ClonableView, HashTag, HashableView, Hasher, View, ViewError,
+        MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -715,9 +718,13 @@ where
         #[cfg(with_metrics)]
         let _hash_latency = BUCKET_QUEUE_VIEW_HASH_RUNTIME.measure_latency();
         let elements = self.elements().await?;
-        let mut hasher = sha3::Sha3_256::default();
-        hasher.update_with_bcs_bytes(&elements)?;
-        Ok(hasher.finalize())
+        if elements.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        HashTag::Queue
+            .keyed_hash_serializable::<sha3::Sha3_256, _>(self.context.hash_key(), &elements)
     }
 }
 
@@ -5,14 +5,17 @@
 use std::sync::LazyLock;
 use std::{
     borrow::Borrow,
-    collections::{btree_map, BTreeMap},
-    io::Write,
+    collections::{btree_map, BTreeMap, BTreeSet},
+    io::Write as _,
     marker::PhantomData,
     mem,
+    sync::Mutex,
 };
 
 use async_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use futures::stream::{self, StreamExt as _, TryStreamExt as _};
 use serde::{de::DeserializeOwned, Serialize};
+use tokio_util::sync::CancellationToken;
 #[cfg(with_metrics)]
 use {
     linera_base::prometheus_util::{
@@ -26,8 +29,12 @@ use crate::{
     common::{CustomSerialize, HasherOutput, Update},
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
+    merkle::{self, MerkleTree},
     store::{KeyIterable, ReadableKeyValueStore as _},
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        empty_sentinel_hash, ClonableView, DynHasher, HashTag, HashableView, Hasher,
+        MultiHashableView, View, ViewError, EMPTY_SENTINEL_HASH, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -41,6 +48,9 @@ static COLLECTION_VIEW_HASH_RUNTIME: LazyLock<HistogramVec> = LazyLock::new(|| {
     )
 });
 
+/// The default number of entries hashed concurrently by `hash_with_concurrency`.
+pub const DEFAULT_HASH_CONCURRENCY: usize = 16;
+
 /// A view that supports accessing a collection of views of the same kind, indexed by a
 /// `Vec<u8>`, one subview at a time.
 #[derive(Debug)]
@@ -48,6 +58,18 @@ pub struct ByteCollectionView<C, W> {
     context: C,
     delete_storage_first: bool,
     updates: RwLock<BTreeMap<Vec<u8>, Update<W>>>,
+    hash_cache: Mutex<HashCache>,
+}
+
+/// The Merkle tree of the entries of a collection, as of the last hash computation.
+#[derive(Clone, Debug, Default)]
+struct HashCache {
+    /// The keys of the entries, in the order of the leaves of `tree`.
+    keys: Vec<Vec<u8>>,
+    /// The tree whose leaves commit to the keys and the hashes of the entries.
+    tree: MerkleTree,
+    /// The keys of the entries that may have changed since their leaf was computed.
+    stale: BTreeSet<Vec<u8>>,
 }
 
 /// A read-only accessor for a particular subview in a [`CollectionView`].
@@ -103,6 +125,7 @@ where
             context,
             delete_storage_first: false,
             updates: RwLock::new(BTreeMap::new()),
+            hash_cache: Mutex::new(HashCache::default()),
         })
     }
 
@@ -112,6 +135,7 @@ where
 
     fn rollback(&mut self) {
         self.delete_storage_first = false;
+        self.mark_updates_stale();
         self.updates.get_mut().clear();
     }
 
@@ -124,6 +148,7 @@ where
     }
 
     fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.mark_updates_stale();
         let mut delete_view = false;
         if self.delete_storage_first {
             delete_view = true;
@@ -157,6 +182,7 @@ where
 
     fn clear(&mut self) {
         self.delete_storage_first = true;
+        self.mark_updates_stale();
         self.updates.get_mut().clear();
     }
 }
@@ -185,6 +211,7 @@ where
             context: self.context.clone(),
             delete_storage_first: self.delete_storage_first,
             updates: RwLock::new(cloned_updates),
+            hash_cache: Mutex::new(self.hash_cache.get_mut().unwrap().clone()),
         })
     }
 }
@@ -212,6 +239,14 @@ where
         batch.put_key_value_bytes(key, vec![]);
     }
 
+    /// Records that the entries loaded in memory may no longer match the cached leaves of
+    /// the Merkle tree once they are dropped.
+    fn mark_updates_stale(&mut self) {
+        let updates = self.updates.get_mut();
+        let cache = self.hash_cache.get_mut().unwrap();
+        cache.stale.extend(updates.keys().cloned());
+    }
+
     /// Loads a subview for the data at the given index in the collection. If an entry
     /// is absent then a default entry is added to the collection. The resulting view
     /// can be modified.
@@ -643,66 +678,479 @@ where
     type Hasher = sha3::Sha3_256;
 
     async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.hash_with_concurrency(1).await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
         let keys = self.keys().await?;
-        let count = keys.len() as u32;
-        hasher.update_with_bcs_bytes(&count)?;
-        let updates = self.updates.get_mut();
-        for key in keys {
-            hasher.update_with_bytes(&key)?;
-            let hash = match updates.get_mut(&key) {
-                Some(entry) => {
-                    let Update::Set(view) = entry else {
-                        unreachable!();
-                    };
-                    view.hash_mut().await?
-                }
+        let updates = self.updates.read().await;
+        self.compute_hash(&updates, &BTreeMap::new(), keys, 1, None)
+            .await
+    }
+}
+
+impl<C, W> MultiHashableView<C> for ByteCollectionView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: MultiHashableView<C> + Send + Sync + 'static,
+{
+    /// Each entry is loaded once and hashed with all the hashers, then the hashes of the
+    /// entries are combined as in [`HashableView::hash`]. The Merkle trees are computed
+    /// from scratch.
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
+        let keys = self.keys().await?;
+        let updates = self.updates.read().await;
+        if keys.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(vec![hash.to_vec(); hashers.len()]);
+            }
+        }
+        let mut leaves = vec![Vec::new(); hashers.len()];
+        for key in &keys {
+            let hashes = match updates.get(key) {
+                Some(Update::Set(view)) => view.hash_multi(hashers).await?,
+                Some(Update::Removed) => unreachable!("removed keys are not listed"),
                 None => {
                     let key = self
                         .context
                         .base_key()
-                        .base_tag_index(KeyTag::Subview as u8, &key);
-                    let context = self.context.clone_with_base_key(key);
-                    let mut view = W::load(context).await?;
-                    view.hash_mut().await?
+                        .base_tag_index(KeyTag::Subview as u8, key);
+                    let view = W::load(self.context.clone_with_base_key(key)).await?;
+                    view.hash_multi(hashers).await?
                 }
             };
-            hasher.write_all(hash.as_ref())?;
+            for ((hasher, leaves), hash) in hashers.iter_mut().zip(&mut leaves).zip(hashes) {
+                leaves.push(merkle::dyn_leaf_hash(*hasher, key, &hash)?);
+            }
         }
-        Ok(hasher.finalize())
+        hashers
+            .iter_mut()
+            .zip(leaves)
+            .map(|(hasher, leaves)| {
+                let root = merkle::dyn_merkle_root(*hasher, leaves)?;
+                HashTag::Collection.reset_dyn_hasher(*hasher, self.context.hash_key())?;
+                hasher.write_all(&root)?;
+                Ok(hasher.finalize_bytes())
+            })
+            .collect()
     }
+}
 
-    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+impl<C, W> ByteCollectionView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + Send + Sync + 'static,
+{
+    /// Computes the same hash as [`HashableView::hash_mut`], while hashing up to
+    /// `concurrency` entries at the same time. The entry hashes are still combined in the
+    /// order of the keys, so the result does not depend on `concurrency`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// let hash = view.hash_with_concurrency(4).await.unwrap();
+    /// assert_eq!(hash, view.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub async fn hash_with_concurrency(
+        &mut self,
+        concurrency: usize,
+    ) -> Result<HasherOutput, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
         let keys = self.keys().await?;
-        let count = keys.len() as u32;
-        hasher.update_with_bcs_bytes(&count)?;
+        let loaded_hashes = self.hash_mut_loaded_entries(concurrency, None).await?;
+        let updates = self.updates.read().await;
+        self.compute_hash(&updates, &loaded_hashes, keys, concurrency, None)
+            .await
+    }
+
+    /// Computes the same hash as [`HashableView::hash_mut`], unless the token is cancelled
+    /// before all the entries are hashed. In that case, this stops before hashing the next
+    /// entry and returns [`ViewError::Cancelled`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View, ViewError};
+    /// # use tokio_util::sync::CancellationToken;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// let token = CancellationToken::new();
+    /// let hash = view.hash_with_cancel(token.clone()).await.unwrap();
+    /// assert_eq!(hash, view.hash().await.unwrap());
+    /// token.cancel();
+    /// assert!(matches!(
+    ///     view.hash_with_cancel(token).await,
+    ///     Err(ViewError::Cancelled)
+    /// ));
+    /// # })
+    /// ```
+    pub async fn hash_with_cancel(
+        &mut self,
+        token: CancellationToken,
+    ) -> Result<HasherOutput, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
+        let keys = self.keys().await?;
+        let loaded_hashes = self.hash_mut_loaded_entries(1, Some(&token)).await?;
+        let updates = self.updates.read().await;
+        self.compute_hash(&updates, &loaded_hashes, keys, 1, Some(&token))
+            .await
+    }
+
+    /// Returns the keys of the collection together with the hashes of their entries, in
+    /// the order of the keys used by [`HashableView::hash`]. Comparing the results for two
+    /// versions of the collection tells which entries differ.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// let entry_hashes = view.entry_hashes().await.unwrap();
+    /// let entry = view.try_load_entry(&[0, 1]).await.unwrap().unwrap();
+    /// assert_eq!(entry_hashes, vec![(vec![0, 1], entry.hash().await.unwrap())]);
+    /// # })
+    /// ```
+    pub async fn entry_hashes(
+        &self,
+    ) -> Result<Vec<(Vec<u8>, <W::Hasher as Hasher>::Output)>, ViewError> {
+        let keys = self.keys().await?;
+        let updates = self.updates.read().await;
+        let hashes = self
+            .hash_entries(&updates, &BTreeMap::new(), keys.iter(), 1, None)
+            .await?;
+        Ok(keys.into_iter().zip(hashes).collect())
+    }
+
+    /// Returns the keys whose entries differ between this collection and `other`,
+    /// including the keys present in only one of them, and a digest committing to these
+    /// differences. The digest covers the number of differing keys, then for each of them,
+    /// in order, the key and the hashes of its entry in both collections, if any.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context.clone()).await.unwrap();
+    /// let mut other: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap().set("Hello".to_string());
+    /// other.load_entry_mut(&[0, 1]).await.unwrap().set("Hello".to_string());
+    /// other.load_entry_mut(&[0, 2]).await.unwrap();
+    /// let (keys, _digest) = view.diff(&other).await.unwrap();
+    /// assert_eq!(keys.into_iter().collect::<Vec<_>>(), vec![vec![0, 2]]);
+    /// # })
+    /// ```
+    pub async fn diff(&self, other: &Self) -> Result<(BTreeSet<Vec<u8>>, HasherOutput), ViewError> {
+        let hashes = self
+            .entry_hashes()
+            .await?
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        let other_hashes = other
+            .entry_hashes()
+            .await?
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        let keys = hashes
+            .keys()
+            .chain(other_hashes.keys())
+            .filter(|key| hashes.get(*key) != other_hashes.get(*key))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        let mut hasher =
+            HashTag::CollectionDiff.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bcs_sequence_length(keys.len())?;
+        for key in &keys {
+            hasher.update_with_bcs_bytes(key)?;
+            hasher.update_with_bcs_bytes(&hashes.get(key))?;
+            hasher.update_with_bcs_bytes(&other_hashes.get(key))?;
+        }
+        Ok((keys, hasher.finalize()))
+    }
+
+    /// Hashes the entries with the given keys, e.g. the part of the collection assigned to
+    /// one worker. Keys that are not in the collection are ignored. The result can be
+    /// folded with those of other, disjoint, sets of keys, see [`SubsetHash`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// view.load_entry_mut(&[0, 2]).await.unwrap();
+    /// let first = view.hash_subset(&[vec![0, 1]]).await.unwrap();
+    /// let second = view.hash_subset(&[vec![0, 2]]).await.unwrap();
+    /// let hash = first.fold(second).unwrap().hash().unwrap();
+    /// assert_eq!(hash, view.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub async fn hash_subset(&self, keys: &[Vec<u8>]) -> Result<SubsetHash, ViewError> {
+        let present_keys = self.keys().await?.into_iter().collect::<BTreeSet<_>>();
+        let keys = keys
+            .iter()
+            .filter(|key| present_keys.contains(*key))
+            .cloned()
+            .collect::<BTreeSet<_>>();
         let updates = self.updates.read().await;
-        for key in keys {
-            hasher.update_with_bytes(&key)?;
-            let hash = match updates.get(&key) {
-                Some(entry) => {
-                    let Update::Set(view) = entry else {
-                        unreachable!();
-                    };
-                    view.hash().await?
+        let hashes = self
+            .hash_entries(&updates, &BTreeMap::new(), keys.iter(), 1, None)
+            .await?;
+        let leaves = keys
+            .into_iter()
+            .zip(hashes)
+            .map(|(key, hash)| {
+                let leaf = merkle::leaf_hash(&key, hash.as_ref())?;
+                Ok((key, leaf))
+            })
+            .collect::<Result<_, ViewError>>()?;
+        Ok(SubsetHash {
+            hash_key: self.context.hash_key().copied(),
+            empty_sentinel: self.context.empty_sentinel(),
+            leaves,
+        })
+    }
+
+    /// Computes the hash of the collection: the root of the Merkle tree whose leaves
+    /// commit to the keys and the hashes of the entries, in the order of the keys.
+    ///
+    /// The tree of the previous computation is reused: only the entries loaded in memory
+    /// or modified since then are hashed again. If the keys did not change, only the
+    /// ancestors of the leaves of these entries are recomputed. The hashes in
+    /// `loaded_hashes` are used as they are. If the computation fails or is cancelled, the
+    /// cache is left empty.
+    async fn compute_hash(
+        &self,
+        updates: &BTreeMap<Vec<u8>, Update<W>>,
+        loaded_hashes: &BTreeMap<Vec<u8>, <W::Hasher as Hasher>::Output>,
+        keys: Vec<Vec<u8>>,
+        concurrency: usize,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<HasherOutput, ViewError> {
+        if keys.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        // Concurrent computations start from an empty cache, which is always correct.
+        let mut cache = mem::take(&mut *self.hash_cache.lock().unwrap());
+        let is_stale = |key: &Vec<u8>| updates.contains_key(key) || cache.stale.contains(key);
+        if cache.keys == keys {
+            let positions = (0..keys.len())
+                .filter(|position| is_stale(&keys[*position]))
+                .collect::<Vec<_>>();
+            let hashes = self
+                .hash_entries(
+                    updates,
+                    loaded_hashes,
+                    positions.iter().map(|position| &keys[*position]),
+                    concurrency,
+                    cancel,
+                )
+                .await?;
+            for (position, hash) in positions.into_iter().zip(hashes) {
+                let leaf = merkle::leaf_hash(&keys[position], hash.as_ref())?;
+                cache.tree.update(position, leaf)?;
+            }
+        } else {
+            let mut cached_leaves = cache
+                .keys
+                .iter()
+                .zip(cache.tree.leaves())
+                .filter(|(key, _)| !is_stale(key))
+                .map(|(key, leaf)| (key.as_slice(), *leaf))
+                .collect::<BTreeMap<_, _>>();
+            let missing_keys = keys
+                .iter()
+                .filter(|key| !cached_leaves.contains_key(key.as_slice()))
+                .collect::<Vec<_>>();
+            let hashes = self
+                .hash_entries(
+                    updates,
+                    loaded_hashes,
+                    missing_keys.iter().copied(),
+                    concurrency,
+                    cancel,
+                )
+                .await?;
+            for (key, hash) in missing_keys.into_iter().zip(hashes) {
+                cached_leaves.insert(key, merkle::leaf_hash(key, hash.as_ref())?);
+            }
+            let leaves = keys
+                .iter()
+                .map(|key| cached_leaves[key.as_slice()])
+                .collect();
+            let tree = MerkleTree::new(leaves)?;
+            cache = HashCache {
+                keys,
+                tree,
+                stale: BTreeSet::new(),
+            };
+        }
+        cache.stale.clear();
+        let root = cache.tree.root()?;
+        *self.hash_cache.lock().unwrap() = cache;
+        let mut hasher =
+            HashTag::Collection.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bytes(&root)?;
+        Ok(hasher.finalize())
+    }
+
+    /// Hashes the entries loaded in memory with [`HashableView::hash_mut`], so that they
+    /// can memoize their hashes, up to `concurrency` at the same time. No entry is hashed
+    /// after `cancel` is cancelled.
+    async fn hash_mut_loaded_entries(
+        &mut self,
+        concurrency: usize,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<BTreeMap<Vec<u8>, <W::Hasher as Hasher>::Output>, ViewError> {
+        let views = self
+            .updates
+            .get_mut()
+            .iter_mut()
+            .filter_map(|(key, update)| match update {
+                Update::Set(view) => Some((key, view)),
+                Update::Removed => None,
+            });
+        stream::iter(views)
+            .map(|(key, view)| async move {
+                if cancel.is_some_and(CancellationToken::is_cancelled) {
+                    return Err(ViewError::Cancelled);
                 }
-                None => {
-                    let key = self
-                        .context
-                        .base_key()
-                        .base_tag_index(KeyTag::Subview as u8, &key);
-                    let context = self.context.clone_with_base_key(key);
-                    let view = W::load(context).await?;
-                    view.hash().await?
+                Ok((key.clone(), view.hash_mut().await?))
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await
+    }
+
+    /// Computes the hashes of the entries with the given keys, up to `concurrency` at the
+    /// same time, taking those in `loaded_hashes` as they are. No entry is hashed after
+    /// `cancel` is cancelled.
+    async fn hash_entries<'a>(
+        &self,
+        updates: &BTreeMap<Vec<u8>, Update<W>>,
+        loaded_hashes: &BTreeMap<Vec<u8>, <W::Hasher as Hasher>::Output>,
+        keys: impl Iterator<Item = &'a Vec<u8>> + Send,
+        concurrency: usize,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Vec<<W::Hasher as Hasher>::Output>, ViewError> {
+        let context = &self.context;
+        stream::iter(keys)
+            .map(|key| async move {
+                if cancel.is_some_and(CancellationToken::is_cancelled) {
+                    return Err(ViewError::Cancelled);
                 }
-            };
-            hasher.write_all(hash.as_ref())?;
+                if let Some(hash) = loaded_hashes.get(key) {
+                    return Ok(hash.clone());
+                }
+                match updates.get(key) {
+                    Some(entry) => {
+                        let Update::Set(view) = entry else {
+                            unreachable!();
+                        };
+                        view.hash().await
+                    }
+                    None => {
+                        let key = context
+                            .base_key()
+                            .base_tag_index(KeyTag::Subview as u8, key);
+                        let view = W::load(context.clone_with_base_key(key)).await?;
+                        view.hash().await
+                    }
+                }
+            })
+            .buffered(concurrency.max(1))
+            .try_collect()
+            .await
+    }
+}
+
+/// The commitment to some of the entries of a collection view, computed by
+/// [`ByteCollectionView::hash_subset`]. It holds the leaves of the Merkle tree of these
+/// entries, by key.
+///
+/// The subsets of a collection are combined with [`SubsetHash::fold`], in any order, as
+/// long as they are disjoint. The [`SubsetHash::hash`] of the result is the hash that the
+/// collection would have if it only contained the entries of these subsets: once all the
+/// entries are covered, it is the hash of the collection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubsetHash {
+    hash_key: Option<[u8; 32]>,
+    empty_sentinel: bool,
+    leaves: BTreeMap<Vec<u8>, HasherOutput>,
+}
+
+impl SubsetHash {
+    /// Returns the number of entries in the subset.
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Returns whether the subset has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Combines the hashes of two disjoint subsets of the same collection. Fails with
+    /// [`ViewError::OverlappingSubsets`] if a key is in both subsets, and with
+    /// [`ViewError::MismatchedSubsets`] if the subsets are not hashed the same way, e.g.
+    /// with different hash keys.
+    pub fn fold(mut self, other: SubsetHash) -> Result<SubsetHash, ViewError> {
+        if (self.hash_key, self.empty_sentinel) != (other.hash_key, other.empty_sentinel) {
+            return Err(ViewError::MismatchedSubsets);
+        }
+        for (key, leaf) in other.leaves {
+            match self.leaves.entry(key) {
+                btree_map::Entry::Occupied(_) => return Err(ViewError::OverlappingSubsets),
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(leaf);
+                }
+            }
         }
+        Ok(self)
+    }
+
+    /// Computes the hash of a collection with the entries of the subset.
+    pub fn hash(&self) -> Result<HasherOutput, ViewError> {
+        if self.leaves.is_empty() && self.empty_sentinel {
+            return Ok(HasherOutput::from(EMPTY_SENTINEL_HASH));
+        }
+        let root = merkle::merkle_root(self.leaves.values().copied().collect())?;
+        let mut hasher =
+            HashTag::Collection.keyed_hasher::<sha3::Sha3_256>(self.hash_key.as_ref())?;
+        hasher.update_with_bytes(&root)?;
         Ok(hasher.finalize())
     }
 }
@@ -1086,6 +1534,103 @@ where
     }
 }
 
+impl<C, I, W> MultiHashableView<C> for CollectionView<C, I, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Clone + Send + Sync + Serialize + DeserializeOwned,
+    W: MultiHashableView<C> + Send + Sync + 'static,
+{
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.hash_multi(hashers).await
+    }
+}
+
+impl<C, I, W> CollectionView<C, I, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + Send + Sync + 'static,
+{
+    /// Computes the same hash as [`HashableView::hash_mut`], while hashing up to
+    /// `concurrency` entries at the same time.
+    pub async fn hash_with_concurrency(
+        &mut self,
+        concurrency: usize,
+    ) -> Result<HasherOutput, ViewError> {
+        self.collection.hash_with_concurrency(concurrency).await
+    }
+
+    /// Computes the same hash as [`HashableView::hash_mut`], unless the token is cancelled
+    /// first. See [`ByteCollectionView::hash_with_cancel`].
+    pub async fn hash_with_cancel(
+        &mut self,
+        token: CancellationToken,
+    ) -> Result<HasherOutput, ViewError> {
+        self.collection.hash_with_cancel(token).await
+    }
+
+    /// Returns the indices of the collection together with the hashes of their entries,
+    /// in the order used by [`HashableView::hash`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// view.load_entry_mut(&25).await.unwrap();
+    /// let entry_hashes = view.entry_hashes().await.unwrap();
+    /// assert_eq!(entry_hashes.len(), 2);
+    /// assert_eq!(entry_hashes[0].0, 23);
+    /// # })
+    /// ```
+    pub async fn entry_hashes(&self) -> Result<Vec<(I, <W::Hasher as Hasher>::Output)>, ViewError>
+    where
+        I: DeserializeOwned,
+    {
+        self.collection
+            .entry_hashes()
+            .await?
+            .into_iter()
+            .map(|(key, hash)| Ok((BaseKey::deserialize_value(&key)?, hash)))
+            .collect()
+    }
+
+    /// Returns the indices whose entries differ between this collection and `other`, and
+    /// a digest committing to these differences. See [`ByteCollectionView::diff`].
+    pub async fn diff(&self, other: &Self) -> Result<(BTreeSet<I>, HasherOutput), ViewError>
+    where
+        I: Ord + DeserializeOwned,
+    {
+        let (keys, digest) = self.collection.diff(&other.collection).await?;
+        let indices = keys
+            .iter()
+            .map(|key| BaseKey::deserialize_value(key))
+            .collect::<Result<_, _>>()?;
+        Ok((indices, digest))
+    }
+
+    /// Hashes the entries with the given indices. See [`ByteCollectionView::hash_subset`].
+    pub async fn hash_subset<Q>(&self, indices: &[Q]) -> Result<SubsetHash, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize,
+    {
+        let keys = indices
+            .iter()
+            .map(BaseKey::derive_short_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.collection.hash_subset(&keys).await
+    }
+}
+
 /// A map view that serializes the indices.
 #[derive(Debug)]
 pub struct CustomCollectionView<C, I, W> {
@@ -1465,6 +2010,21 @@ where
     }
 }
 
+impl<C, I, W> MultiHashableView<C> for CustomCollectionView<C, I, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Clone + Send + Sync + CustomSerialize,
+    W: MultiHashableView<C> + Send + Sync + 'static,
+{
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.collection.hash_multi(hashers).await
+    }
+}
+
 /// Type wrapping `ByteCollectionView` while memoizing the hash.
 pub type HashedByteCollectionView<C, W> =
     WrappedHashableContainerView<C, ByteCollectionView<C, W>, HasherOutput>;
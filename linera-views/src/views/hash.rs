@@ -0,0 +1,268 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{cmp::Ordering, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::views::{HashableView, Hasher, ViewError};
+
+/// The reasons why hashing a view can fail.
+#[derive(Debug, Error)]
+pub enum HashError {
+    /// A value could not be serialized.
+    #[error("Failed to serialize a value to hash: {0}")]
+    Serialization(#[from] bcs::Error),
+
+    /// The values could not be read from the storage.
+    #[error("Failed to read the values to hash: {0}")]
+    Backend(Box<ViewError>),
+
+    /// A value to hash is missing from the storage.
+    #[error("A value to hash is missing: {0}")]
+    MissingValue(String),
+
+    /// The computation was cancelled.
+    #[error("The hash computation was cancelled")]
+    Cancelled,
+}
+
+impl From<ViewError> for HashError {
+    fn from(error: ViewError) -> Self {
+        match error {
+            ViewError::BcsError(error) => HashError::Serialization(error),
+            ViewError::MissingEntries => HashError::MissingValue("missing entries".to_string()),
+            ViewError::NotFound(message) => HashError::MissingValue(message),
+            ViewError::Cancelled => HashError::Cancelled,
+            error => HashError::Backend(Box::new(error)),
+        }
+    }
+}
+
+impl From<HashError> for ViewError {
+    fn from(error: HashError) -> Self {
+        match error {
+            HashError::Serialization(error) => ViewError::BcsError(error),
+            HashError::Backend(error) => *error,
+            HashError::MissingValue(message) => ViewError::NotFound(message),
+            HashError::Cancelled => ViewError::Cancelled,
+        }
+    }
+}
+
+/// Computes the hash of `view`, telling apart the reasons why it failed.
+/// ```rust
+/// # tokio_test::block_on(async {
+/// # use linera_views::context::MemoryContext;
+/// # use linera_views::hash::hash_view;
+/// # use linera_views::register_view::RegisterView;
+/// # use linera_views::views::{HashableView, View};
+/// # let context = MemoryContext::new_for_testing(());
+/// let mut register = RegisterView::<_, u64>::load(context).await.unwrap();
+/// register.set(7);
+/// let hash = hash_view(&register).await.unwrap();
+/// assert_eq!(hash, register.hash().await.unwrap());
+/// # })
+/// ```
+pub async fn hash_view<C, V: HashableView<C>>(
+    view: &V,
+) -> Result<<V::Hasher as Hasher>::Output, HashError> {
+    Ok(view.hash().await?)
+}
+
+/// Everything needed to implement [`HashableView`] and compute the hashes of views, with the
+/// supported hashers:
+/// ```rust
+/// use linera_views::hash::prelude::*;
+/// ```
+pub mod prelude {
+    pub use blake3::Hasher as Blake3;
+    pub use sha2::Sha256;
+    pub use sha3::{Keccak256, Sha3_256};
+
+    pub use super::{hash_view, HashError, ViewHash};
+    pub use crate::{
+        batch::Batch,
+        common::HasherOutput,
+        context::Context,
+        views::{
+            DynHasher, HashTag, HashableView, Hasher, MultiHashableView, View, ViewError,
+            Xxh3Hasher,
+        },
+    };
+}
+
+/// The maximal length of a [`ViewHash`], in bytes.
+const MAX_LEN: usize = 64;
+
+/// The hash of a view, independently of the type of the output of its hasher. Only 32-byte
+/// and 64-byte hashes are supported.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewHash {
+    /// The number of bytes of the hash.
+    len: u8,
+    /// The bytes of the hash, followed by zeros.
+    bytes: [u8; MAX_LEN],
+}
+
+impl ViewHash {
+    /// Computes the hash of `view`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::hash::ViewHash;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut register = RegisterView::<_, u64>::load(context).await.unwrap();
+    /// register.set(7);
+    /// let hash = ViewHash::of(&register).await.unwrap();
+    /// assert_eq!(hash.as_ref(), register.hash().await.unwrap().as_slice());
+    /// # })
+    /// ```
+    pub async fn of<C, V: HashableView<C>>(view: &V) -> Result<Self, ViewError> {
+        Self::try_from(view.hash().await?.as_ref())
+    }
+}
+
+impl TryFrom<&[u8]> for ViewHash {
+    type Error = ViewError;
+
+    fn try_from(value: &[u8]) -> Result<Self, ViewError> {
+        if value.len() != 32 && value.len() != MAX_LEN {
+            return Err(ViewError::UnsupportedHashLength(value.len()));
+        }
+        let mut bytes = [0; MAX_LEN];
+        bytes[..value.len()].copy_from_slice(value);
+        Ok(ViewHash {
+            len: value.len() as u8,
+            bytes,
+        })
+    }
+}
+
+impl AsRef<[u8]> for ViewHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes[..usize::from(self.len)]
+    }
+}
+
+impl Ord for ViewHash {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp(other.as_ref())
+    }
+}
+
+impl PartialOrd for ViewHash {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for ViewHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self))
+    }
+}
+
+impl fmt::Debug for ViewHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ViewHash({self})")
+    }
+}
+
+impl Serialize for ViewHash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            serializer.serialize_bytes(self.as_ref())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ViewHash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            let string = String::deserialize(deserializer)?;
+            hex::decode(string).map_err(serde::de::Error::custom)?
+        } else {
+            Vec::<u8>::deserialize(deserializer)?
+        };
+        ViewHash::try_from(bytes.as_slice()).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A floating-point value with a single representation of zero and of NaN, so that views
+/// holding it have the same hash on all nodes: `-0.0` is stored as `0.0`, and all NaNs as
+/// [`f64::NAN`]. Values are canonicalized when created and when deserialized. BCS does not
+/// support floats, so binary formats store the bits of the value instead. Human-readable
+/// formats store the value as a string, so that NaN and infinities are preserved, e.g. in
+/// JSON.
+/// ```rust
+/// # use linera_views::hash::CanonicalFloat;
+/// assert_eq!(CanonicalFloat::from(-0.0), CanonicalFloat::from(0.0));
+/// assert_eq!(
+///     bcs::to_bytes(&CanonicalFloat::from(f64::from_bits(0x7ff8_0000_0000_0001))).unwrap(),
+///     bcs::to_bytes(&CanonicalFloat::from(f64::NAN)).unwrap(),
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CanonicalFloat(f64);
+
+impl CanonicalFloat {
+    /// Returns the canonical value.
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<f64> for CanonicalFloat {
+    fn from(value: f64) -> Self {
+        if value.is_nan() {
+            CanonicalFloat(f64::NAN)
+        } else if value == 0.0 {
+            CanonicalFloat(0.0)
+        } else {
+            CanonicalFloat(value)
+        }
+    }
+}
+
+impl From<CanonicalFloat> for f64 {
+    fn from(value: CanonicalFloat) -> Self {
+        value.0
+    }
+}
+
+impl PartialEq for CanonicalFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for CanonicalFloat {}
+
+impl Serialize for CanonicalFloat {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.0.to_string())
+        } else {
+            serializer.serialize_u64(self.0.to_bits())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CanonicalFloat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = if deserializer.is_human_readable() {
+            String::deserialize(deserializer)?
+                .parse::<f64>()
+                .map_err(serde::de::Error::custom)?
+        } else {
+            f64::from_bits(u64::deserialize(deserializer)?)
+        };
+        Ok(CanonicalFloat::from(value))
+    }
+}
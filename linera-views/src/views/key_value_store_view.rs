@@ -36,7 +36,7 @@ use crate::{
     context::Context,
     map_view::ByteMapView,
     store::{KeyIterable, KeyValueIterable, ReadableKeyValueStore},
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{ClonableView, HashTag, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
 
 #[cfg(with_metrics)]
@@ -1159,7 +1159,8 @@ where
     async fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = KEY_VALUE_STORE_VIEW_HASH_LATENCY.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
+        let mut hasher =
+            HashTag::KeyValueStore.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
         let mut count = 0u32;
         self.for_each_index_value(|index, value| -> Result<(), ViewError> {
             count += 1;
@@ -1,11 +1,14 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::ops::{Bound, Range, RangeBounds};
 #[cfg(with_metrics)]
 use std::sync::LazyLock;
+use std::{
+    ops::{Bound, Range, RangeBounds},
+    sync::Mutex,
+};
 
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 #[cfg(with_metrics)]
 use {
     linera_base::prometheus_util::{
@@ -19,8 +22,12 @@ use crate::{
     common::{from_bytes_option_or_default, HasherOutput},
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
+    merkle::{self, MerkleProof},
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        empty_sentinel_hash, BlockBufferedHasher, ClonableView, HashTag, HashableView, Hasher,
+        View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -43,6 +50,71 @@ enum KeyTag {
     Index,
 }
 
+/// The number of values of a log that are hashed together, see [`HashState`].
+pub const HASH_CHUNK_SIZE: usize = 64;
+
+/// The progress of a hash computation over a log, see [`LogView::hash_resumable`].
+///
+/// The values of a log are hashed by chunks of [`HASH_CHUNK_SIZE`] values, each chunk
+/// together with the digest of the previous ones. The hash of the log is the final digest
+/// followed by the number of values. Since the state of the computation is only a digest,
+/// it can be serialized, e.g. to resume the computation after a restart.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashState {
+    /// The number of values of the log already hashed.
+    position: usize,
+    /// The digest of the chunks hashed so far.
+    digest: HasherOutput,
+}
+
+impl HashState {
+    /// Returns the number of values of the log already hashed.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the state of a computation that has not hashed any value yet.
+    fn new() -> Self {
+        HashState {
+            position: 0,
+            digest: HasherOutput::default(),
+        }
+    }
+
+    /// Hashes the values following the ones already hashed. The chunks are complete, except
+    /// maybe the last one: no value can be hashed after an incomplete chunk.
+    fn absorb<T: Serialize>(&mut self, values: &[T]) -> Result<(), ViewError> {
+        for chunk in values.chunks(HASH_CHUNK_SIZE) {
+            let mut hasher = HashTag::Log.hasher::<sha3::Sha3_256>()?;
+            hasher.update_with_bytes(&self.digest)?;
+            let mut writer = BlockBufferedHasher::new(hasher);
+            for value in chunk {
+                bcs::serialize_into(&mut writer, value)?;
+            }
+            self.digest = writer.into_inner()?.finalize();
+            self.position += chunk.len();
+        }
+        Ok(())
+    }
+
+    /// Computes the hash of the values hashed so far, with the given hash key if any.
+    fn finalize(&self, hash_key: Option<&[u8; 32]>) -> Result<HasherOutput, ViewError> {
+        let mut hasher = HashTag::Log.keyed_hasher::<sha3::Sha3_256>(hash_key)?;
+        hasher.update_with_bytes(&self.digest)?;
+        hasher.update_with_bcs_bytes(&self.position)?;
+        Ok(hasher.finalize())
+    }
+}
+
+/// The result of a slice of a resumable hash computation.
+#[derive(Clone, Debug)]
+pub enum HashProgress {
+    /// All the values were hashed: this is the hash of the log.
+    Done(HasherOutput),
+    /// Some values remain to be hashed, starting from this state.
+    Pending(HashState),
+}
+
 /// A view that supports logging values of type `T`.
 #[derive(Debug)]
 pub struct LogView<C, T> {
@@ -50,6 +122,8 @@ pub struct LogView<C, T> {
     delete_storage_first: bool,
     stored_count: usize,
     new_values: Vec<T>,
+    /// The hash state after the complete chunks of stored values hashed so far.
+    hash_cache: Mutex<Option<HashState>>,
 }
 
 impl<C, T> View<C> for LogView<C, T>
@@ -76,6 +150,7 @@ where
             delete_storage_first: false,
             stored_count,
             new_values: Vec::new(),
+            hash_cache: Mutex::new(None),
         })
     }
 
@@ -88,6 +163,7 @@ where
     fn rollback(&mut self) {
         self.delete_storage_first = false;
         self.new_values.clear();
+        self.reset_hash_cache();
     }
 
     async fn has_pending_changes(&self) -> bool {
@@ -102,6 +178,7 @@ where
         if self.delete_storage_first {
             batch.delete_key_prefix(self.context.base_key().bytes.clone());
             self.stored_count = 0;
+            self.reset_hash_cache();
             delete_view = true;
         }
         if !self.new_values.is_empty() {
@@ -140,6 +217,7 @@ where
             delete_storage_first: self.delete_storage_first,
             stored_count: self.stored_count,
             new_values: self.new_values.clone(),
+            hash_cache: Mutex::new(self.hash_cache.get_mut().unwrap().clone()),
         })
     }
 }
@@ -188,6 +266,11 @@ where
     pub fn extra(&self) -> &C::Extra {
         self.context.extra()
     }
+
+    /// Discards the cached hash state, forcing the next hash to read all stored values.
+    pub(crate) fn reset_hash_cache(&mut self) {
+        *self.hash_cache.get_mut().unwrap() = None;
+    }
 }
 
 impl<C, T> LogView<C, T>
@@ -269,6 +352,22 @@ where
         Ok(result)
     }
 
+    /// Returns the hash state after the complete chunks of stored values, starting from the
+    /// cached state so that only the values stored since the last computation are read.
+    async fn stored_hash_state(&self) -> Result<HashState, ViewError> {
+        if self.delete_storage_first {
+            return Ok(HashState::new());
+        }
+        let cache = self.hash_cache.lock().unwrap().clone();
+        let mut state = cache.unwrap_or_else(HashState::new);
+        let end = self.stored_count - self.stored_count % HASH_CHUNK_SIZE;
+        if state.position < end {
+            state.absorb(&self.read_context(state.position..end).await?)?;
+            *self.hash_cache.lock().unwrap() = Some(state.clone());
+        }
+        Ok(state)
+    }
+
     async fn read_context(&self, range: Range<usize>) -> Result<Vec<T>, ViewError> {
         let count = range.len();
         let mut keys = Vec::with_capacity(count);
@@ -347,6 +446,151 @@ where
             )
         }
     }
+
+    /// Computes the hash of the values in the given range, in the same way as the hash of
+    /// the whole log. In particular, the range `0..count` has the same hash as the view.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::log_view::LogView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut log = LogView::load(context).await.unwrap();
+    /// log.push(34);
+    /// log.push(42);
+    /// let hash = log.hash_range(0..2).await.unwrap();
+    /// assert_eq!(hash, log.hash().await.unwrap());
+    /// assert!(log.hash_range(1..3).await.is_err());
+    /// # })
+    /// ```
+    pub async fn hash_range(&self, range: Range<usize>) -> Result<HasherOutput, ViewError> {
+        if range.start > range.end || range.end > self.count() {
+            return Err(ViewError::RangeOutOfBounds(range));
+        }
+        if range.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        let mut state = HashState::new();
+        state.absorb(&self.read(range).await?)?;
+        state.finalize(self.context.hash_key())
+    }
+
+    /// Hashes about `max_count` more values of the log, starting from `state` or from the
+    /// beginning of the log. The values are hashed by whole chunks, so `max_count` is
+    /// rounded up to a multiple of [`HASH_CHUNK_SIZE`]. Once all the values are hashed, this
+    /// returns the same hash as [`HashableView::hash`], so that hashing a large log can be
+    /// split in several steps. The state can be serialized in between, but it must be
+    /// resumed with the same log.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::log_view::{HashProgress, LogView, HASH_CHUNK_SIZE};
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut log = LogView::load(context).await.unwrap();
+    /// for value in 0..100u32 {
+    ///     log.push(value);
+    /// }
+    /// let HashProgress::Pending(state) = log.hash_resumable(None, 1).await.unwrap() else {
+    ///     unreachable!();
+    /// };
+    /// assert_eq!(state.position(), HASH_CHUNK_SIZE);
+    /// let bytes = bcs::to_bytes(&state).unwrap();
+    /// let state = bcs::from_bytes(&bytes).unwrap();
+    /// let progress = log.hash_resumable(Some(state), 1).await.unwrap();
+    /// assert!(matches!(progress, HashProgress::Done(hash) if hash == log.hash().await.unwrap()));
+    /// # })
+    /// ```
+    pub async fn hash_resumable(
+        &self,
+        state: Option<HashState>,
+        max_count: usize,
+    ) -> Result<HashProgress, ViewError> {
+        let mut state = state.unwrap_or_else(HashState::new);
+        let count = self.count();
+        if state.position > count || state.position % HASH_CHUNK_SIZE != 0 {
+            return Err(ViewError::RangeOutOfBounds(state.position..count));
+        }
+        if count == 0 {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(HashProgress::Done(hash));
+            }
+        }
+        let max_count = max_count
+            .div_ceil(HASH_CHUNK_SIZE)
+            .max(1)
+            .saturating_mul(HASH_CHUNK_SIZE);
+        let end = count.min(state.position.saturating_add(max_count));
+        state.absorb(&self.read(state.position..end).await?)?;
+        if end < count {
+            return Ok(HashProgress::Pending(state));
+        }
+        Ok(HashProgress::Done(state.finalize(self.context.hash_key())?))
+    }
+
+    /// Computes the leaves of the Merkle tree of the log: the hashes of the indices and
+    /// values, in the order of the log.
+    async fn merkle_leaves(&self) -> Result<Vec<HasherOutput>, ViewError> {
+        self.read(..)
+            .await?
+            .iter()
+            .enumerate()
+            .map(|(index, value)| Self::merkle_leaf(index, value))
+            .collect()
+    }
+
+    fn merkle_leaf(index: usize, value: &T) -> Result<HasherOutput, ViewError> {
+        merkle::leaf_hash(&bcs::to_bytes(&index)?, &bcs::to_bytes(value)?)
+    }
+
+    /// Computes the root of the Merkle tree whose leaves are the values of the log, in
+    /// order. When a level of the tree has an odd number of nodes, the last one is moved up
+    /// unchanged, and the root commits to the number of values. Contrary to the hash of the
+    /// view, this commitment allows proving a single value with [`LogView::prove`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::log_view::LogView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut log = LogView::<_, u32>::load(context).await.unwrap();
+    /// log.push(34);
+    /// log.push(42);
+    /// let root = log.merkle_root().await.unwrap();
+    /// let proof = log.prove(1).await.unwrap().unwrap();
+    /// assert!(LogView::<MemoryContext<()>, u32>::verify_proof(&root, 1, &42, &proof).unwrap());
+    /// assert!(!LogView::<MemoryContext<()>, u32>::verify_proof(&root, 0, &42, &proof).unwrap());
+    /// # })
+    /// ```
+    pub async fn merkle_root(&self) -> Result<HasherOutput, ViewError> {
+        merkle::merkle_root(self.merkle_leaves().await?)
+    }
+
+    /// Creates a proof that the value at `index` is committed by [`LogView::merkle_root`].
+    /// Returns `None` if the index is out of bounds.
+    pub async fn prove(&self, index: usize) -> Result<Option<MerkleProof>, ViewError> {
+        if index >= self.count() {
+            return Ok(None);
+        }
+        let leaves = self.merkle_leaves().await?;
+        Ok(Some(merkle::merkle_proof(leaves, index)?))
+    }
+
+    /// Checks that `proof` shows that `value` is at `index` in the log committed by `root`.
+    pub fn verify_proof(
+        root: &HasherOutput,
+        index: usize,
+        value: &T,
+        proof: &MerkleProof,
+    ) -> Result<bool, ViewError> {
+        if proof.position != index {
+            return Ok(false);
+        }
+        let leaf = Self::merkle_leaf(index, value)?;
+        Ok(matches!(proof.root(leaf)?, Some(computed_root) if computed_root == *root))
+    }
 }
 
 impl<C, T> HashableView<C> for LogView<C, T>
@@ -364,10 +608,14 @@ where
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = LOG_VIEW_HASH_RUNTIME.measure_latency();
-        let elements = self.read(..).await?;
-        let mut hasher = sha3::Sha3_256::default();
-        hasher.update_with_bcs_bytes(&elements)?;
-        Ok(hasher.finalize())
+        if self.count() == 0 {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        let mut state = self.stored_hash_state().await?;
+        state.absorb(&self.read(state.position..self.count()).await?)?;
+        state.finalize(self.context.hash_key())
     }
 }
 
@@ -40,9 +40,11 @@ static MAP_VIEW_HASH_RUNTIME: LazyLock<HistogramVec> = LazyLock::new(|| {
 
 use std::{
     borrow::{Borrow, Cow},
-    collections::{btree_map::Entry, BTreeMap},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    io::Write as _,
     marker::PhantomData,
     mem,
+    sync::Mutex,
 };
 
 use serde::{de::DeserializeOwned, Serialize};
@@ -55,8 +57,12 @@ use crate::{
     },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
+    merkle::{self, MerkleProof, MerkleTree},
     store::{KeyIterable, KeyValueIterable, ReadableKeyValueStore as _},
-    views::{ClonableView, HashableView, Hasher, View, ViewError},
+    views::{
+        empty_sentinel_hash, ClonableView, DynHasher, HashTag, HashableView, Hasher,
+        MultiHashableView, View, ViewError,
+    },
 };
 
 /// A view that supports inserting and removing values indexed by `Vec<u8>`.
@@ -65,6 +71,20 @@ pub struct ByteMapView<C, V> {
     context: C,
     deletion_set: DeletionSet,
     updates: BTreeMap<Vec<u8>, Update<V>>,
+    hash_cache: Mutex<HashCache>,
+}
+
+/// The Merkle tree of the entries of a map, as of the last hash computation.
+#[derive(Clone, Debug, Default)]
+struct HashCache {
+    /// Whether the keys were framed when computing the leaves.
+    frame_indices: bool,
+    /// The keys of the entries, in the order of the leaves of `tree`.
+    keys: Vec<Vec<u8>>,
+    /// The tree whose leaves commit to the entries.
+    tree: MerkleTree,
+    /// The keys of the entries that may have changed since their leaf was computed.
+    stale: BTreeSet<Vec<u8>>,
 }
 
 /// Whether we have a value or its serialization.
@@ -122,6 +142,7 @@ where
             context,
             updates: BTreeMap::new(),
             deletion_set: DeletionSet::new(),
+            hash_cache: Mutex::new(HashCache::default()),
         })
     }
 
@@ -130,6 +151,7 @@ where
     }
 
     fn rollback(&mut self) {
+        self.mark_updates_stale();
         self.updates.clear();
         self.deletion_set.rollback();
     }
@@ -139,6 +161,7 @@ where
     }
 
     fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.mark_updates_stale();
         let mut delete_view = false;
         if self.deletion_set.delete_storage_first {
             delete_view = true;
@@ -168,6 +191,7 @@ where
     }
 
     fn clear(&mut self) {
+        self.mark_updates_stale();
         self.updates.clear();
         self.deletion_set.clear();
     }
@@ -184,6 +208,7 @@ where
             context: self.context.clone(),
             updates: self.updates.clone(),
             deletion_set: self.deletion_set.clone(),
+            hash_cache: Mutex::new(self.hash_cache.get_mut().unwrap().clone()),
         })
     }
 }
@@ -193,6 +218,13 @@ where
     C: Context,
     ViewError: From<C::Error>,
 {
+    /// Marks the entries with pending updates as stale in the hash cache, before the
+    /// updates are discarded.
+    fn mark_updates_stale(&mut self) {
+        let cache = self.hash_cache.get_mut().unwrap();
+        cache.stale.extend(self.updates.keys().cloned());
+    }
+
     /// Inserts or resets the value of a key of the map.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -921,37 +953,458 @@ where
     }
 }
 
-impl<C, V> HashableView<C> for ByteMapView<C, V>
+impl<C, V> ByteMapView<C, V>
 where
     C: Context + Send + Sync,
     ViewError: From<C::Error>,
     V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
 {
-    type Hasher = sha3::Sha3_256;
+    /// Computes the Merkle leaf of an entry. If `frame_indices` is set, the key is prefixed
+    /// with its length, as BCS would do for a `Vec<u8>`, so that a `ByteMapView` has the same
+    /// hash as a `MapView` with `Vec<u8>` indices. Otherwise, the keys are expected to be
+    /// self-delimiting, e.g. BCS serializations of a fixed type, and are hashed as they are.
+    fn hash_leaf(frame_indices: bool, key: &[u8], value: &[u8]) -> Result<HasherOutput, ViewError> {
+        if frame_indices {
+            merkle::leaf_hash(key, value)
+        } else {
+            merkle::serialized_key_leaf_hash(key, value)
+        }
+    }
 
-    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
-        self.hash().await
+    /// Reads the serialization of the values of the given keys, which must be in the map.
+    async fn read_value_bytes(&self, keys: &[&Vec<u8>]) -> Result<Vec<Vec<u8>>, ViewError> {
+        let stored_keys = keys
+            .iter()
+            .filter(|key| !self.updates.contains_key(**key))
+            .map(|key| self.context.base_key().base_index(key))
+            .collect::<Vec<_>>();
+        let mut stored_values = self
+            .context
+            .store()
+            .read_multi_values_bytes(stored_keys)
+            .await?
+            .into_iter();
+        keys.iter()
+            .map(|key| match self.updates.get(*key) {
+                Some(Update::Set(value)) => Ok(bcs::to_bytes(value)?),
+                Some(Update::Removed) => unreachable!("removed keys are not listed"),
+                None => stored_values
+                    .next()
+                    .flatten()
+                    .ok_or(ViewError::MissingEntries),
+            })
+            .collect()
     }
 
-    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+    /// Hashes the root of the Merkle tree of the entries.
+    fn hash_merkle_root(&self, root: &HasherOutput) -> Result<HasherOutput, ViewError> {
+        let mut hasher = HashTag::Map.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bytes(root)?;
+        Ok(hasher.finalize())
+    }
+
+    /// Hashes the Merkle tree of the entries, in the lexicographic order of their keys.
+    /// The keys in `exclude` are skipped, as if they were not in the map. When no key is
+    /// excluded, the tree of the last computation is reused: only the leaves of the entries
+    /// updated since then are recomputed.
+    async fn hash_entries(
+        &self,
+        frame_indices: bool,
+        exclude: &BTreeSet<Vec<u8>>,
+    ) -> Result<HasherOutput, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = MAP_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
-        let mut count = 0u32;
-        let prefix = Vec::new();
+        let mut keys = self.keys().await?;
+        keys.sort_unstable();
+        keys.retain(|key| !exclude.contains(key));
+        if keys.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        let root = if exclude.is_empty() {
+            self.cached_merkle_root(frame_indices, keys).await?
+        } else {
+            let keys = keys.iter().collect::<Vec<_>>();
+            let values = self.read_value_bytes(&keys).await?;
+            let leaves = keys
+                .into_iter()
+                .zip(values)
+                .map(|(key, value)| Self::hash_leaf(frame_indices, key, &value))
+                .collect::<Result<_, _>>()?;
+            merkle::merkle_root(leaves)?
+        };
+        self.hash_merkle_root(&root)
+    }
+
+    /// Computes the root of the Merkle tree of the entries with the given sorted keys,
+    /// updating the cached tree.
+    async fn cached_merkle_root(
+        &self,
+        frame_indices: bool,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<HasherOutput, ViewError> {
+        self.with_cached_merkle_tree(frame_indices, keys, MerkleTree::root)
+            .await
+    }
+
+    /// Brings the cached Merkle tree up to date with the entries with the given sorted
+    /// keys, then applies `f` to it.
+    async fn with_cached_merkle_tree<R>(
+        &self,
+        frame_indices: bool,
+        keys: Vec<Vec<u8>>,
+        f: impl FnOnce(&MerkleTree) -> Result<R, ViewError>,
+    ) -> Result<R, ViewError> {
+        // Concurrent computations start from an empty cache, which is always correct.
+        let mut cache = mem::take(&mut *self.hash_cache.lock().unwrap());
+        if cache.frame_indices != frame_indices {
+            cache = HashCache {
+                frame_indices,
+                ..HashCache::default()
+            };
+        }
+        let updates = &self.updates;
+        let is_stale = |key: &Vec<u8>| updates.contains_key(key) || cache.stale.contains(key);
+        if cache.keys == keys {
+            let positions = (0..keys.len())
+                .filter(|position| is_stale(&keys[*position]))
+                .collect::<Vec<_>>();
+            let stale_keys = positions
+                .iter()
+                .map(|position| &keys[*position])
+                .collect::<Vec<_>>();
+            let values = self.read_value_bytes(&stale_keys).await?;
+            for (position, value) in positions.into_iter().zip(values) {
+                let leaf = Self::hash_leaf(frame_indices, &keys[position], &value)?;
+                cache.tree.update(position, leaf)?;
+            }
+        } else {
+            let mut cached_leaves = cache
+                .keys
+                .iter()
+                .zip(cache.tree.leaves())
+                .filter(|(key, _)| !is_stale(key))
+                .map(|(key, leaf)| (key.as_slice(), *leaf))
+                .collect::<BTreeMap<_, _>>();
+            let missing_keys = keys
+                .iter()
+                .filter(|key| !cached_leaves.contains_key(key.as_slice()))
+                .collect::<Vec<_>>();
+            let values = self.read_value_bytes(&missing_keys).await?;
+            for (key, value) in missing_keys.into_iter().zip(values) {
+                cached_leaves.insert(key, Self::hash_leaf(frame_indices, key, &value)?);
+            }
+            let leaves = keys
+                .iter()
+                .map(|key| cached_leaves[key.as_slice()])
+                .collect();
+            let tree = MerkleTree::new(leaves)?;
+            cache = HashCache {
+                frame_indices,
+                keys,
+                tree,
+                stale: BTreeSet::new(),
+            };
+        }
+        cache.stale.clear();
+        let result = f(&cache.tree)?;
+        *self.hash_cache.lock().unwrap() = cache;
+        Ok(result)
+    }
+
+    /// Same as [`ByteMapView::hash_entries`] but only the keys are loaded at once: the
+    /// stored values are read by batches of `batch_size` entries, and the Merkle tree is
+    /// computed from scratch.
+    async fn hash_entries_in_batches(
+        &self,
+        frame_indices: bool,
+        batch_size: usize,
+    ) -> Result<HasherOutput, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = MAP_VIEW_HASH_RUNTIME.measure_latency();
+        let mut keys = self.keys().await?;
+        if keys.is_empty() {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        keys.sort_unstable();
+        let mut leaves = Vec::with_capacity(keys.len());
+        for batch in keys.chunks(batch_size.max(1)) {
+            let batch = batch.iter().collect::<Vec<_>>();
+            let values = self.read_value_bytes(&batch).await?;
+            for (key, value) in batch.into_iter().zip(values) {
+                leaves.push(Self::hash_leaf(frame_indices, key, &value)?);
+            }
+        }
+        let root = merkle::merkle_root(leaves)?;
+        self.hash_merkle_root(&root)
+    }
+
+    /// Computes the same hash as [`HashableView::hash`] while holding at most
+    /// `batch_size` values in memory. This is meant for very large maps.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![0, 2], String::from("Bonjour"));
+    /// let hash = map.hash_in_batches(1).await.unwrap();
+    /// assert_eq!(hash, map.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub async fn hash_in_batches(&self, batch_size: usize) -> Result<HasherOutput, ViewError> {
+        self.hash_entries_in_batches(true, batch_size).await
+    }
+
+    /// Computes the hash of the map as if the given keys were not in it. With no keys to
+    /// exclude, this is the same as [`HashableView::hash`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use std::collections::BTreeSet;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// let hash = map.hash().await.unwrap();
+    /// map.insert(vec![0, 2], String::from("Bonjour"));
+    /// let exclude = BTreeSet::from([vec![0, 2]]);
+    /// assert_eq!(map.hash_excluding(&exclude).await.unwrap(), hash);
+    /// # })
+    /// ```
+    pub async fn hash_excluding(
+        &self,
+        exclude: &BTreeSet<Vec<u8>>,
+    ) -> Result<HasherOutput, ViewError> {
+        self.hash_entries(true, exclude).await
+    }
+
+    /// Hashes the sorted keys of the map, prefixed with their number, without reading
+    /// any value. The keys are framed like in [`ByteMapView::hash_leaf`].
+    async fn hash_key_set(&self, frame_indices: bool) -> Result<HasherOutput, ViewError> {
+        let mut keys = self.keys().await?;
+        keys.sort_unstable();
+        let mut hasher =
+            HashTag::MapKeys.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bcs_sequence_length(keys.len())?;
+        for key in &keys {
+            if frame_indices {
+                hasher.update_with_bcs_bytes(key)?;
+            } else {
+                hasher.update_with_bytes(key)?;
+            }
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Hashes only the keys of the map, ignoring the values, e.g. to detect structural
+    /// changes cheaply: the values are never read. The hash only changes when a key is
+    /// added or removed.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// let hash = map.hash_keys().await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Bonjour"));
+    /// assert_eq!(map.hash_keys().await.unwrap(), hash);
+    /// # })
+    /// ```
+    pub async fn hash_keys(&self) -> Result<HasherOutput, ViewError> {
+        self.hash_key_set(true).await
+    }
+
+    /// Computes the hash of [`ByteMapView::hash_entries`] with each of the given hashers,
+    /// reading the entries only once. The Merkle trees are computed from scratch.
+    async fn hash_entries_multi(
+        &self,
+        frame_indices: bool,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = MAP_VIEW_HASH_RUNTIME.measure_latency();
+        let mut leaves = vec![Vec::new(); hashers.len()];
         self.for_each_key_value_or_bytes(
-            |index, value| {
-                count += 1;
-                hasher.update_with_bytes(index)?;
+            |key, value| {
                 let bytes = value.into_bytes()?;
-                hasher.update_with_bytes(&bytes)?;
+                for (hasher, leaves) in hashers.iter_mut().zip(&mut leaves) {
+                    let leaf = if frame_indices {
+                        merkle::dyn_leaf_hash(*hasher, key, &bytes)?
+                    } else {
+                        merkle::dyn_serialized_key_leaf_hash(*hasher, key, &bytes)?
+                    };
+                    leaves.push(leaf);
+                }
                 Ok(())
             },
-            prefix,
+            Vec::new(),
         )
         .await?;
-        hasher.update_with_bcs_bytes(&count)?;
-        Ok(hasher.finalize())
+        if leaves.first().is_some_and(Vec::is_empty) {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(vec![hash.to_vec(); hashers.len()]);
+            }
+        }
+        hashers
+            .iter_mut()
+            .zip(leaves)
+            .map(|(hasher, leaves)| {
+                let root = merkle::dyn_merkle_root(*hasher, leaves)?;
+                HashTag::Map.reset_dyn_hasher(*hasher, self.context.hash_key())?;
+                hasher.write_all(&root)?;
+                Ok(hasher.finalize_bytes())
+            })
+            .collect()
+    }
+}
+
+impl<C, V> HashableView<C> for ByteMapView<C, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    type Hasher = sha3::Sha3_256;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.hash().await
+    }
+
+    /// The entries are the leaves of a Merkle tree, in the lexicographic order of their
+    /// keys, so that maps with the same entries have the same hash regardless of the
+    /// storage backend. The tree is kept between calls, so that hashing again after a few
+    /// updates only recomputes the paths from the updated leaves.
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.hash_entries(true, &BTreeSet::new()).await
+    }
+}
+
+impl<C, V> MultiHashableView<C> for ByteMapView<C, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Hashes the entries once for all the hashers. See [`HashableView::hash`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::{HashableView, MultiHashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// let mut sha3_hasher = sha3::Sha3_256::default();
+    /// let mut blake3_hasher = blake3::Hasher::default();
+    /// let hashes = map
+    ///     .hash_multi(&mut [&mut sha3_hasher, &mut blake3_hasher])
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(hashes[0], map.hash().await.unwrap().to_vec());
+    /// # })
+    /// ```
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.hash_entries_multi(true, hashers).await
+    }
+}
+
+impl<C, V> ByteMapView<C, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Computes the root of the Merkle tree that [`ByteMapView::hash_entries`] commits to,
+    /// reusing the cached tree.
+    async fn merkle_root_with(&self, frame_indices: bool) -> Result<HasherOutput, ViewError> {
+        let mut keys = self.keys().await?;
+        keys.sort_unstable();
+        self.cached_merkle_root(frame_indices, keys).await
+    }
+
+    /// Creates a proof that the entry for `short_key` is a leaf of the tree returned by
+    /// [`ByteMapView::merkle_root_with`].
+    async fn prove_with(
+        &self,
+        frame_indices: bool,
+        short_key: &[u8],
+    ) -> Result<Option<MerkleProof>, ViewError> {
+        let mut keys = self.keys().await?;
+        keys.sort_unstable();
+        let Ok(position) = keys.binary_search_by(|key| key.as_slice().cmp(short_key)) else {
+            return Ok(None);
+        };
+        let proof = self
+            .with_cached_merkle_tree(frame_indices, keys, |tree| tree.proof(position))
+            .await?;
+        Ok(Some(proof))
+    }
+
+    /// Checks that `proof` shows that the entry `(key, value)` is a leaf of the tree with
+    /// the given root, the leaf being computed as in [`ByteMapView::hash_leaf`].
+    fn verify_proof_with(
+        frame_indices: bool,
+        root: &HasherOutput,
+        key: &[u8],
+        value: &V,
+        proof: &MerkleProof,
+    ) -> Result<bool, ViewError> {
+        let leaf = Self::hash_leaf(frame_indices, key, &bcs::to_bytes(value)?)?;
+        Ok(matches!(proof.root(leaf), Ok(Some(computed_root)) if computed_root == *root))
+    }
+
+    /// Computes the root of the Merkle tree whose leaves are the entries of the map, in
+    /// the lexicographic order of the keys. This is the root committed by the hash of the
+    /// view, and it allows proving a single entry with [`ByteMapView::prove`].
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![0, 2], String::from("Bonjour"));
+    /// let root = map.merkle_root().await.unwrap();
+    /// let proof = map.prove(&[0, 2]).await.unwrap().unwrap();
+    /// let value = String::from("Bonjour");
+    /// assert!(ByteMapView::<MemoryContext<()>, String>::verify_proof(
+    ///     &root, &[0, 2], &value, &proof
+    /// )
+    /// .unwrap());
+    /// # })
+    /// ```
+    pub async fn merkle_root(&self) -> Result<HasherOutput, ViewError> {
+        self.merkle_root_with(true).await
+    }
+
+    /// Creates a proof that the entry for the given key is committed by
+    /// [`ByteMapView::merkle_root`]. Returns `None` if the key is missing.
+    pub async fn prove(&self, short_key: &[u8]) -> Result<Option<MerkleProof>, ViewError> {
+        self.prove_with(true, short_key).await
+    }
+
+    /// Checks that `proof` shows that `short_key` is associated with `value` in the map
+    /// committed by `root`.
+    pub fn verify_proof(
+        root: &HasherOutput,
+        short_key: &[u8],
+        value: &V,
+        proof: &MerkleProof,
+    ) -> Result<bool, ViewError> {
+        Self::verify_proof_with(true, root, short_key, value, proof)
     }
 }
 
@@ -1441,11 +1894,109 @@ where
     type Hasher = sha3::Sha3_256;
 
     async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
-        self.map.hash_mut().await
+        self.hash().await
     }
 
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
-        self.map.hash().await
+        self.map.hash_entries(false, &BTreeSet::new()).await
+    }
+}
+
+impl<C, I, V> MultiHashableView<C> for MapView<C, I, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + Serialize + DeserializeOwned,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.map.hash_entries_multi(false, hashers).await
+    }
+}
+
+impl<C, I, V> MapView<C, I, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Serialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Computes the root of the Merkle tree whose leaves are the entries of the map.
+    /// See [`ByteMapView::merkle_root`].
+    pub async fn merkle_root(&self) -> Result<HasherOutput, ViewError> {
+        self.map.merkle_root_with(false).await
+    }
+
+    /// Computes the same hash as [`HashableView::hash`] while holding at most
+    /// `batch_size` values in memory. See [`ByteMapView::hash_in_batches`].
+    pub async fn hash_in_batches(&self, batch_size: usize) -> Result<HasherOutput, ViewError> {
+        self.map.hash_entries_in_batches(false, batch_size).await
+    }
+
+    /// Computes the hash of the map as if the given indices were not in it. See
+    /// [`ByteMapView::hash_excluding`].
+    pub async fn hash_excluding(&self, exclude: &BTreeSet<I>) -> Result<HasherOutput, ViewError> {
+        let exclude = exclude
+            .iter()
+            .map(BaseKey::derive_short_key)
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        self.map.hash_entries(false, &exclude).await
+    }
+
+    /// Hashes only the indices of the map, without reading the values. See
+    /// [`ByteMapView::hash_keys`].
+    pub async fn hash_keys(&self) -> Result<HasherOutput, ViewError> {
+        self.map.hash_key_set(false).await
+    }
+
+    /// Creates a proof that the entry for the given index is committed by
+    /// [`MapView::merkle_root`]. Returns `None` if the index is missing.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, u32, String> = MapView::load(context).await.unwrap();
+    /// map.insert(&(37 as u32), String::from("Hello"));
+    /// let root = map.merkle_root().await.unwrap();
+    /// let proof = map.prove(&(37 as u32)).await.unwrap().unwrap();
+    /// let value = String::from("Hello");
+    /// assert!(MapView::<MemoryContext<()>, u32, String>::verify_proof(
+    ///     &root,
+    ///     &(37 as u32),
+    ///     &value,
+    ///     &proof
+    /// )
+    /// .unwrap());
+    /// # })
+    /// ```
+    pub async fn prove<Q>(&self, index: &Q) -> Result<Option<MerkleProof>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.prove_with(false, &short_key).await
+    }
+
+    /// Checks that `proof` shows that `index` is associated with `value` in the map
+    /// committed by `root`.
+    pub fn verify_proof<Q>(
+        root: &HasherOutput,
+        index: &Q,
+        value: &V,
+        proof: &MerkleProof,
+    ) -> Result<bool, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        ByteMapView::<C, V>::verify_proof_with(false, root, &short_key, value, proof)
     }
 }
 
@@ -1939,11 +2490,26 @@ where
     type Hasher = sha3::Sha3_256;
 
     async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
-        self.map.hash_mut().await
+        self.hash().await
     }
 
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
-        self.map.hash().await
+        self.map.hash_entries(false, &BTreeSet::new()).await
+    }
+}
+
+impl<C, I, V> MultiHashableView<C> for CustomMapView<C, I, V>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    I: Send + Sync + CustomSerialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        self.map.hash_entries_multi(false, hashers).await
     }
 }
 
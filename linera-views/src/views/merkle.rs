@@ -0,0 +1,283 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::io::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::HasherOutput,
+    views::{DynHasher, Hasher, ViewError},
+};
+
+/// Tags separating the hashes of the different kinds of nodes of a Merkle tree.
+#[repr(u8)]
+enum NodeTag {
+    /// Prefix for the hash of a leaf.
+    Leaf,
+    /// Prefix for the hash of an inner node.
+    Inner,
+    /// Prefix for the hash of the root, committing to the number of leaves.
+    Root,
+}
+
+/// A proof that a leaf belongs to a Merkle tree. The leaves are built differently by each
+/// view, so proofs are checked by the view that produced them, with
+/// [`crate::map_view::ByteMapView::verify_proof`], [`crate::map_view::MapView::verify_proof`]
+/// or [`crate::log_view::LogView::verify_proof`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// The position of the leaf in the tree.
+    pub position: usize,
+    /// The number of leaves of the tree.
+    pub count: usize,
+    /// The hashes of the siblings on the path from the leaf to the root.
+    pub siblings: Vec<HasherOutput>,
+}
+
+impl MerkleProof {
+    /// Computes the root of the tree that contains `leaf`, according to this proof.
+    /// Returns `None` if the proof is malformed.
+    pub fn root(&self, leaf: HasherOutput) -> Result<Option<HasherOutput>, ViewError> {
+        if self.position >= self.count {
+            return Ok(None);
+        }
+        let mut node = leaf;
+        let mut index = self.position;
+        let mut size = self.count;
+        let mut siblings = self.siblings.iter();
+        while size > 1 {
+            if index ^ 1 < size {
+                let Some(sibling) = siblings.next() else {
+                    return Ok(None);
+                };
+                node = if index % 2 == 0 {
+                    inner_hash(&node, sibling)?
+                } else {
+                    inner_hash(sibling, &node)?
+                };
+            }
+            index /= 2;
+            size = size.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return Ok(None);
+        }
+        Ok(Some(root_hash(self.count, Some(&node))?))
+    }
+}
+
+/// Computes the hash of the leaf for a key and the serialization of its value. The key is
+/// prefixed with its length.
+pub fn leaf_hash(key: &[u8], value: &[u8]) -> Result<HasherOutput, ViewError> {
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bytes(&[NodeTag::Leaf as u8])?;
+    hasher.update_with_bcs_bytes(&key)?;
+    hasher.update_with_bcs_bytes(&value)?;
+    Ok(hasher.finalize())
+}
+
+/// Same as [`leaf_hash`] for a key that is already self-delimiting, e.g. the BCS
+/// serialization of a fixed type, which is not prefixed with its length.
+pub fn serialized_key_leaf_hash(key: &[u8], value: &[u8]) -> Result<HasherOutput, ViewError> {
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bytes(&[NodeTag::Leaf as u8])?;
+    hasher.update_with_bytes(key)?;
+    hasher.update_with_bcs_bytes(&value)?;
+    Ok(hasher.finalize())
+}
+
+fn inner_hash(left: &HasherOutput, right: &HasherOutput) -> Result<HasherOutput, ViewError> {
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bytes(&[NodeTag::Inner as u8])?;
+    hasher.update_with_bytes(left)?;
+    hasher.update_with_bytes(right)?;
+    Ok(hasher.finalize())
+}
+
+fn root_hash(count: usize, top: Option<&HasherOutput>) -> Result<HasherOutput, ViewError> {
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bytes(&[NodeTag::Root as u8])?;
+    hasher.update_with_bcs_bytes(&count)?;
+    if let Some(top) = top {
+        hasher.update_with_bytes(top)?;
+    }
+    Ok(hasher.finalize())
+}
+
+/// Computes the parents of a level of the tree. An odd node out is moved up unchanged.
+fn next_level(nodes: &[HasherOutput]) -> Result<Vec<HasherOutput>, ViewError> {
+    nodes
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => inner_hash(left, right),
+            [single] => Ok(*single),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Computes the root of the Merkle tree with the given leaves.
+pub fn merkle_root(mut leaves: Vec<HasherOutput>) -> Result<HasherOutput, ViewError> {
+    let count = leaves.len();
+    while leaves.len() > 1 {
+        leaves = next_level(&leaves)?;
+    }
+    root_hash(count, leaves.first())
+}
+
+/// Same as [`leaf_hash`], with a hasher of any type, which is reset first.
+pub fn dyn_leaf_hash(
+    hasher: &mut dyn DynHasher,
+    key: &[u8],
+    value: &[u8],
+) -> Result<Vec<u8>, ViewError> {
+    hasher.reset_keyed(None)?;
+    hasher.write_all(&[NodeTag::Leaf as u8])?;
+    bcs::serialize_into(&mut *hasher, key)?;
+    bcs::serialize_into(&mut *hasher, value)?;
+    Ok(hasher.finalize_bytes())
+}
+
+/// Same as [`serialized_key_leaf_hash`], with a hasher of any type, which is reset first.
+pub fn dyn_serialized_key_leaf_hash(
+    hasher: &mut dyn DynHasher,
+    key: &[u8],
+    value: &[u8],
+) -> Result<Vec<u8>, ViewError> {
+    hasher.reset_keyed(None)?;
+    hasher.write_all(&[NodeTag::Leaf as u8])?;
+    hasher.write_all(key)?;
+    bcs::serialize_into(&mut *hasher, value)?;
+    Ok(hasher.finalize_bytes())
+}
+
+/// Same as [`merkle_root`], with a hasher of any type, which is reset first.
+pub fn dyn_merkle_root(
+    hasher: &mut dyn DynHasher,
+    mut leaves: Vec<Vec<u8>>,
+) -> Result<Vec<u8>, ViewError> {
+    let count = leaves.len();
+    while leaves.len() > 1 {
+        leaves = leaves
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    hasher.reset_keyed(None)?;
+                    hasher.write_all(&[NodeTag::Inner as u8])?;
+                    hasher.write_all(left)?;
+                    hasher.write_all(right)?;
+                    Ok(hasher.finalize_bytes())
+                }
+                [single] => Ok(single.clone()),
+                _ => unreachable!(),
+            })
+            .collect::<Result<_, ViewError>>()?;
+    }
+    hasher.reset_keyed(None)?;
+    hasher.write_all(&[NodeTag::Root as u8])?;
+    bcs::serialize_into(&mut *hasher, &count)?;
+    if let Some(top) = leaves.first() {
+        hasher.write_all(top)?;
+    }
+    Ok(hasher.finalize_bytes())
+}
+
+/// A Merkle tree whose leaves can be replaced one at a time, only recomputing the inner
+/// nodes on the path from the leaf to the root.
+#[derive(Clone, Debug, Default)]
+pub struct MerkleTree {
+    /// The levels of the tree, starting with the leaves.
+    levels: Vec<Vec<HasherOutput>>,
+}
+
+impl MerkleTree {
+    /// Creates the tree with the given leaves.
+    pub fn new(leaves: Vec<HasherOutput>) -> Result<Self, ViewError> {
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let parents = next_level(level)?;
+            levels.push(parents);
+        }
+        Ok(Self { levels })
+    }
+
+    /// Returns the leaves of the tree.
+    pub fn leaves(&self) -> &[HasherOutput] {
+        self.levels.first().map_or(&[], Vec::as_slice)
+    }
+
+    /// Replaces the leaf at `position` and recomputes its ancestors.
+    pub fn update(&mut self, position: usize, leaf: HasherOutput) -> Result<(), ViewError> {
+        let Some(node) = self
+            .levels
+            .first_mut()
+            .and_then(|leaves| leaves.get_mut(position))
+        else {
+            return Err(ViewError::RangeOutOfBounds(position..position + 1));
+        };
+        *node = leaf;
+        let mut index = position;
+        for height in 1..self.levels.len() {
+            let parent = index / 2;
+            let children = &self.levels[height - 1];
+            let node = match children.get(2 * parent + 1) {
+                Some(right) => inner_hash(&children[2 * parent], right)?,
+                None => children[2 * parent],
+            };
+            self.levels[height][parent] = node;
+            index = parent;
+        }
+        Ok(())
+    }
+
+    /// Computes the root of the tree, as [`merkle_root`] would do for its leaves.
+    pub fn root(&self) -> Result<HasherOutput, ViewError> {
+        let top = self.levels.last().and_then(|level| level.first());
+        root_hash(self.leaves().len(), top)
+    }
+
+    /// Creates the proof that the leaf at `position` belongs to the tree, as
+    /// [`merkle_proof`] would do for its leaves.
+    pub fn proof(&self, position: usize) -> Result<MerkleProof, ViewError> {
+        let count = self.leaves().len();
+        if position >= count {
+            return Err(ViewError::RangeOutOfBounds(position..position + 1));
+        }
+        let mut siblings = Vec::new();
+        let mut index = position;
+        for level in self.levels.iter().filter(|level| level.len() > 1) {
+            if let Some(sibling) = level.get(index ^ 1) {
+                siblings.push(*sibling);
+            }
+            index /= 2;
+        }
+        Ok(MerkleProof {
+            position,
+            count,
+            siblings,
+        })
+    }
+}
+
+/// Creates the proof that the leaf at `position` belongs to the tree with the given leaves.
+pub fn merkle_proof(
+    mut leaves: Vec<HasherOutput>,
+    position: usize,
+) -> Result<MerkleProof, ViewError> {
+    let count = leaves.len();
+    let mut siblings = Vec::new();
+    let mut index = position;
+    while leaves.len() > 1 {
+        if let Some(sibling) = leaves.get(index ^ 1) {
+            siblings.push(*sibling);
+        }
+        leaves = next_level(&leaves)?;
+        index /= 2;
+    }
+    Ok(MerkleProof {
+        position,
+        count,
+        siblings,
+    })
+}
@@ -1,8 +1,9 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{fmt::Debug, io::Write};
+use std::{fmt::Debug, io::Write, pin::pin};
 
+use futures::{Stream, StreamExt as _};
 use linera_base::{
     crypto::CryptoHash,
     data_types::ArithmeticError,
@@ -11,7 +12,7 @@ use linera_base::{
 pub use linera_views_derive::{
     ClonableView, CryptoHashRootView, CryptoHashView, HashableView, RootView, View,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
 use crate::{batch::Batch, common::HasherOutput};
@@ -50,6 +51,17 @@ pub mod key_value_store_view;
 /// Wrapping a view to compute a hash.
 pub mod hashable_wrapper;
 
+/// Wrapping a view to scope its hash to a schema version.
+pub mod versioned_view;
+
+/// Merkle trees committing to the entries of a view.
+pub mod merkle;
+
+/// The `ViewHash` type represents the hash of a view independently of its hasher, the
+/// `HashError` type the failures to compute it, and `CanonicalFloat` floating-point values
+/// with a deterministic hash.
+pub mod hash;
+
 /// The minimum value for the view tags. Values in `0..MIN_VIEW_TAG` are used for other purposes.
 pub const MIN_VIEW_TAG: u8 = 1;
 
@@ -166,6 +178,30 @@ pub enum ViewError {
     /// Some events were not found.
     #[error("Events not found: {0:?}")]
     EventsNotFound(Vec<EventId>),
+
+    /// A range does not fit within the bounds of a view.
+    #[error("Range {0:?} is out of bounds")]
+    RangeOutOfBounds(std::ops::Range<usize>),
+
+    /// A hash could not be decoded from hexadecimal.
+    #[error("Invalid hexadecimal hash: {0}")]
+    InvalidHexHash(String),
+
+    /// A hash does not have one of the supported lengths.
+    #[error("Hashes of {0} bytes are not supported")]
+    UnsupportedHashLength(usize),
+
+    /// The computation was cancelled.
+    #[error("The computation was cancelled")]
+    Cancelled,
+
+    /// Subsets of a collection that were expected to be disjoint have a key in common.
+    #[error("The subsets of a collection overlap")]
+    OverlappingSubsets,
+
+    /// Subsets that were expected to be from the same collection are hashed differently.
+    #[error("The subsets are not from the same collection")]
+    MismatchedSubsets,
 }
 
 impl ViewError {
@@ -196,14 +232,160 @@ pub trait HashableView<C>: View<C> {
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError>;
 }
 
+/// A view that can be hashed with several hash functions at once, e.g. while migrating from
+/// one hash function to another, reading its contents only once.
+#[cfg_attr(not(web), trait_variant::make(Send))]
+pub trait MultiHashableView<C>: HashableView<C> {
+    /// Computes the hash of the view with each of the given hashers, using the same
+    /// encoding as [`HashableView::hash`]. The hashers are reset first, and each result
+    /// is what the hasher would give alone: with [`HashableView::Hasher`], it is the
+    /// output of [`HashableView::hash`].
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError>;
+}
+
+/// The tag written first in the hash of each kind of view, so that views of different
+/// kinds holding equivalent data never have the same hash.
+///
+/// These values are part of the hashes committed by the chains and must never change.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashTag {
+    /// The tag of a `RegisterView`.
+    Register = 1,
+    /// The tag of a `LogView`.
+    Log = 2,
+    /// The tag of a `QueueView` or a `BucketQueueView`.
+    Queue = 3,
+    /// The tag of a `ByteMapView`, a `MapView` or a `CustomMapView`.
+    Map = 4,
+    /// The tag of a collection view, reentrant or not.
+    Collection = 5,
+    /// The tag of a `ByteSetView`, a `SetView` or a `CustomSetView`.
+    Set = 6,
+    /// The tag of a `KeyValueStoreView`.
+    KeyValueStore = 7,
+    /// The tag of a `RegisterView` hashed together with a previous hash, to form a chain.
+    ChainedRegister = 8,
+    /// The tag of the set of keys of a map, without the values.
+    MapKeys = 9,
+    /// The tag of the differences between two versions of a collection view.
+    CollectionDiff = 10,
+    /// The tag of a stream of views hashed together.
+    ViewStream = 11,
+    /// The tag of a view hashed with the version of its schema.
+    Versioned = 12,
+}
+
+impl HashTag {
+    /// Creates a hasher that starts with this tag.
+    pub fn hasher<H: Hasher>(self) -> Result<H, ViewError> {
+        self.keyed_hasher(None)
+    }
+
+    /// Creates a hasher that starts with this tag, keyed with `key` if there is one. See
+    /// [`crate::context::Context::hash_key`].
+    pub fn keyed_hasher<H: Hasher>(self, key: Option<&[u8; 32]>) -> Result<H, ViewError> {
+        let mut hasher = match key {
+            Some(key) => H::new_keyed(key)?,
+            None => H::default(),
+        };
+        hasher.update_with_bytes(&[self as u8])?;
+        Ok(hasher)
+    }
+
+    /// Same as [`HashTag::keyed_hasher`], reusing `hasher`, which is reset first.
+    pub fn reset_hasher<H: Hasher>(
+        self,
+        hasher: &mut H,
+        key: Option<&[u8; 32]>,
+    ) -> Result<(), ViewError> {
+        hasher.reset_with_key(key)?;
+        hasher.update_with_bytes(&[self as u8])
+    }
+
+    /// Same as [`HashTag::keyed_hasher`] for a hasher of any type, which is reset first.
+    pub fn reset_dyn_hasher(
+        self,
+        hasher: &mut dyn DynHasher,
+        key: Option<&[u8; 32]>,
+    ) -> Result<(), ViewError> {
+        hasher.reset_keyed(key)?;
+        hasher.write_all(&[self as u8])?;
+        Ok(())
+    }
+
+    /// Hashes a single serializable value: this tag followed by the BCS serialization of
+    /// the value.
+    pub fn hash_serializable<H: Hasher, T: Serialize>(
+        self,
+        value: &T,
+    ) -> Result<H::Output, ViewError> {
+        self.keyed_hash_serializable::<H, T>(None, value)
+    }
+
+    /// Same as [`HashTag::hash_serializable`], with a hasher keyed with `key` if there is
+    /// one.
+    pub fn keyed_hash_serializable<H: Hasher, T: Serialize>(
+        self,
+        key: Option<&[u8; 32]>,
+        value: &T,
+    ) -> Result<H::Output, ViewError> {
+        let mut hasher = self.keyed_hasher::<H>(key)?;
+        hasher.update_with_bcs_bytes(value)?;
+        Ok(hasher.finalize())
+    }
+}
+
 /// The requirement for the hasher type in [`HashableView`].
 pub trait Hasher: Default + Write + Send + Sync + 'static {
     /// The output type.
-    type Output: Debug + Clone + Eq + AsRef<[u8]> + 'static;
+    type Output: Debug
+        + Clone
+        + Eq
+        + std::hash::Hash
+        + AsRef<[u8]>
+        + Serialize
+        + DeserializeOwned
+        + 'static;
+
+    /// The size in bytes of the blocks processed by the hash function. Feeding the hasher
+    /// with whole blocks, e.g. through a [`BlockBufferedHasher`], avoids buffering partial
+    /// blocks inside the hasher.
+    const BLOCK_SIZE: usize = 64;
 
     /// Finishes the hashing process and returns its output.
     fn finalize(self) -> Self::Output;
 
+    /// Converts bytes into an output, if they have the right length.
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output>;
+
+    /// Creates a hasher keyed with `key`: hashing the same bytes with different keys gives
+    /// unrelated outputs. By default, the key is included in the hash before any data.
+    /// Resetting a keyed hasher may discard the key.
+    fn new_keyed(key: &[u8; 32]) -> Result<Self, ViewError> {
+        let mut hasher = Self::default();
+        hasher.update_with_bytes(key)?;
+        Ok(hasher)
+    }
+
+    /// Resets the hasher to its initial state, so that it can be reused.
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Resets the hasher to the state of [`Hasher::new_keyed`] with `key`, or to its initial
+    /// state if there is no key, so that it can be reused.
+    fn reset_with_key(&mut self, key: Option<&[u8; 32]>) -> Result<(), ViewError> {
+        self.reset();
+        if let Some(key) = key {
+            self.update_with_bytes(key)?;
+        }
+        Ok(())
+    }
+
     /// Serializes a value with BCS and includes it in the hash.
     fn update_with_bcs_bytes(&mut self, value: &impl Serialize) -> Result<(), ViewError> {
         bcs::serialize_into(self, value)?;
@@ -215,14 +397,318 @@ pub trait Hasher: Default + Write + Send + Sync + 'static {
         self.write_all(value)?;
         Ok(())
     }
+
+    /// Includes the length prefix that BCS writes before a sequence of `len` elements.
+    /// Hashing the elements afterwards gives the same result as hashing the whole
+    /// sequence with [`Hasher::update_with_bcs_bytes`].
+    fn update_with_bcs_sequence_length(&mut self, len: usize) -> Result<(), ViewError> {
+        if len > bcs::MAX_SEQUENCE_LENGTH {
+            return Err(bcs::Error::ExceededMaxLen(len).into());
+        }
+        let mut value = len;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.update_with_bytes(&[byte]);
+            }
+            self.update_with_bytes(&[byte | 0x80])?;
+        }
+    }
 }
 
 impl Hasher for sha3::Sha3_256 {
     type Output = HasherOutput;
 
+    const BLOCK_SIZE: usize = 136;
+
     fn finalize(self) -> Self::Output {
         <sha3::Sha3_256 as sha3::Digest>::finalize(self)
     }
+
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output> {
+        generic_array::GenericArray::from_exact_iter(bytes.iter().copied())
+    }
+
+    fn reset(&mut self) {
+        <sha3::Sha3_256 as sha3::Digest>::reset(self)
+    }
+}
+
+impl Hasher for sha2::Sha256 {
+    type Output = sha2::digest::Output<sha2::Sha256>;
+
+    fn finalize(self) -> Self::Output {
+        <sha2::Sha256 as sha2::Digest>::finalize(self)
+    }
+
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output> {
+        generic_array::GenericArray::from_exact_iter(bytes.iter().copied())
+    }
+
+    fn reset(&mut self) {
+        <sha2::Sha256 as sha2::Digest>::reset(self)
+    }
+}
+
+impl Hasher for sha3::Keccak256 {
+    type Output = sha3::digest::Output<sha3::Keccak256>;
+
+    const BLOCK_SIZE: usize = 136;
+
+    fn finalize(self) -> Self::Output {
+        <sha3::Keccak256 as sha3::Digest>::finalize(self)
+    }
+
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output> {
+        generic_array::GenericArray::from_exact_iter(bytes.iter().copied())
+    }
+
+    fn reset(&mut self) {
+        <sha3::Keccak256 as sha3::Digest>::reset(self)
+    }
+}
+
+impl Hasher for blake3::Hasher {
+    type Output = [u8; 32];
+
+    fn finalize(self) -> Self::Output {
+        blake3::Hasher::finalize(&self).into()
+    }
+
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output> {
+        bytes.try_into().ok()
+    }
+
+    fn new_keyed(key: &[u8; 32]) -> Result<Self, ViewError> {
+        Ok(blake3::Hasher::new_keyed(key))
+    }
+
+    fn reset(&mut self) {
+        blake3::Hasher::reset(self);
+    }
+
+    fn reset_with_key(&mut self, key: Option<&[u8; 32]>) -> Result<(), ViewError> {
+        *self = match key {
+            Some(key) => blake3::Hasher::new_keyed(key),
+            None => blake3::Hasher::new(),
+        };
+        Ok(())
+    }
+}
+
+/// A fast, non-cryptographic [`Hasher`] computing 128-bit XXH3 checksums.
+///
+/// This hasher offers no resistance to adversarial collisions. It is only meant to detect
+/// accidental changes, e.g. between two in-memory snapshots of a view, and must not be used
+/// where a cryptographic commitment is expected.
+#[derive(Default)]
+pub struct Xxh3Hasher(twox_hash::xxh3::Hash128);
+
+impl Write for Xxh3Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        std::hash::Hasher::write(&mut self.0, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Hasher for Xxh3Hasher {
+    type Output = [u8; 16];
+
+    fn finalize(self) -> Self::Output {
+        twox_hash::HasherExt::finish_ext(&self.0).to_le_bytes()
+    }
+
+    fn output_from_bytes(bytes: &[u8]) -> Option<Self::Output> {
+        bytes.try_into().ok()
+    }
+}
+
+/// A [`Hasher`] whose type is erased, so that hashers of different types can be used
+/// together, e.g. by [`MultiHashableView::hash_multi`].
+pub trait DynHasher: Write + Send {
+    /// Resets the hasher to the state of [`Hasher::new_keyed`] if there is a key, or to
+    /// its initial state otherwise.
+    fn reset_keyed(&mut self, key: Option<&[u8; 32]>) -> Result<(), ViewError>;
+
+    /// Finishes the hashing process, returns its output as bytes and resets the hasher.
+    fn finalize_bytes(&mut self) -> Vec<u8>;
+}
+
+impl<H: Hasher> DynHasher for H {
+    fn reset_keyed(&mut self, key: Option<&[u8; 32]>) -> Result<(), ViewError> {
+        self.reset_with_key(key)
+    }
+
+    fn finalize_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(self).finalize().as_ref().to_vec()
+    }
+}
+
+/// The number of blocks of the hasher kept by a [`BlockBufferedHasher`].
+const BUFFERED_BLOCKS: usize = 32;
+
+/// A writer feeding a [`Hasher`] by chunks of whole blocks, which is faster than feeding it
+/// with many small serializations. The result is the same as writing to the hasher directly.
+/// ```rust
+/// # use linera_views::views::{BlockBufferedHasher, Hasher};
+/// let mut writer = BlockBufferedHasher::new(sha3::Sha3_256::default());
+/// for value in 0..1000u64 {
+///     bcs::serialize_into(&mut writer, &value).unwrap();
+/// }
+/// let hash = writer.into_inner().unwrap().finalize();
+/// let mut hasher = sha3::Sha3_256::default();
+/// for value in 0..1000u64 {
+///     hasher.update_with_bcs_bytes(&value).unwrap();
+/// }
+/// assert_eq!(hash, hasher.finalize());
+/// ```
+pub struct BlockBufferedHasher<H> {
+    hasher: H,
+    buffer: Vec<u8>,
+}
+
+impl<H: Hasher> BlockBufferedHasher<H> {
+    /// Creates a writer feeding the given hasher.
+    pub fn new(hasher: H) -> Self {
+        BlockBufferedHasher {
+            hasher,
+            buffer: Vec::with_capacity(H::BLOCK_SIZE * BUFFERED_BLOCKS),
+        }
+    }
+
+    /// Feeds the buffered bytes to the hasher and returns it.
+    pub fn into_inner(mut self) -> Result<H, ViewError> {
+        self.hasher.update_with_bytes(&self.buffer)?;
+        Ok(self.hasher)
+    }
+}
+
+impl<H: Hasher> Write for BlockBufferedHasher<H> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let capacity = H::BLOCK_SIZE * BUFFERED_BLOCKS;
+        if self.buffer.len() + buf.len() < capacity {
+            self.buffer.extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+        // Complete the buffer, then feed the whole blocks of the rest directly.
+        let (head, rest) = buf.split_at(capacity - self.buffer.len());
+        self.buffer.extend_from_slice(head);
+        self.hasher.write_all(&self.buffer)?;
+        self.buffer.clear();
+        let whole_blocks = rest.len() - rest.len() % H::BLOCK_SIZE;
+        self.hasher.write_all(&rest[..whole_blocks])?;
+        self.buffer.extend_from_slice(&rest[whole_blocks..]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The hash of every empty map, set, log, queue or collection view whose context sets
+/// [`crate::context::Context::empty_sentinel`].
+pub const EMPTY_SENTINEL_HASH: [u8; 32] = [0; 32];
+
+/// Returns [`EMPTY_SENTINEL_HASH`] if the context asks for it, to be used as the hash of
+/// an empty collection view.
+pub(crate) fn empty_sentinel_hash<C: crate::context::Context>(context: &C) -> Option<HasherOutput> {
+    context
+        .empty_sentinel()
+        .then(|| HasherOutput::from(EMPTY_SENTINEL_HASH))
+}
+
+/// Combines the hashes of several views, e.g. the fields of a composite view, into one:
+/// the number of hashes followed by each of them with its length, in the given order.
+/// ```rust
+/// # tokio_test::block_on(async {
+/// # use linera_views::context::MemoryContext;
+/// # use linera_views::log_view::LogView;
+/// # use linera_views::register_view::RegisterView;
+/// # use linera_views::views::{combine_hashes, HashableView, View};
+/// # let context = MemoryContext::new_for_testing(());
+/// let register = RegisterView::<_, u64>::load(context.clone()).await.unwrap();
+/// let log = LogView::<_, u64>::load(context).await.unwrap();
+/// let hash = combine_hashes::<sha3::Sha3_256, _>([
+///     register.hash().await.unwrap(),
+///     log.hash().await.unwrap(),
+/// ])
+/// .unwrap();
+/// # })
+/// ```
+pub fn combine_hashes<H, I>(hashes: I) -> Result<H::Output, ViewError>
+where
+    H: Hasher,
+    I: IntoIterator,
+    I::IntoIter: ExactSizeIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let hashes = hashes.into_iter();
+    let mut hasher = H::default();
+    hasher.update_with_bcs_sequence_length(hashes.len())?;
+    for hash in hashes {
+        hasher.update_with_bcs_bytes(&hash.as_ref())?;
+    }
+    Ok(hasher.finalize())
+}
+
+/// Hashes the views of a stream, in order, without holding more than one of them at a time.
+/// Since the number of views is not known in advance, the hash of each view is written with
+/// its length, and the number of views is written last. A verifier can replay this with the
+/// hashes of the views:
+/// ```rust
+/// # tokio_test::block_on(async {
+/// # use linera_views::context::MemoryContext;
+/// # use linera_views::register_view::RegisterView;
+/// # use linera_views::views::{hash_view_stream, HashTag, HashableView, Hasher, View};
+/// # let context = MemoryContext::new_for_testing(());
+/// let mut register = RegisterView::<_, u64>::load(context).await.unwrap();
+/// register.set(7);
+/// let sub_hash = register.hash().await.unwrap();
+/// let stream = futures::stream::iter([Ok(register)]);
+/// let hash = hash_view_stream(stream).await.unwrap();
+///
+/// let mut hasher = HashTag::ViewStream.hasher::<sha3::Sha3_256>().unwrap();
+/// hasher.update_with_bcs_bytes(&sub_hash.as_slice()).unwrap();
+/// hasher.update_with_bcs_bytes(&1u64).unwrap();
+/// assert_eq!(hash, hasher.finalize());
+/// # })
+/// ```
+pub async fn hash_view_stream<C, V, S>(
+    stream: S,
+) -> Result<<V::Hasher as Hasher>::Output, ViewError>
+where
+    V: HashableView<C>,
+    S: Stream<Item = Result<V, ViewError>>,
+{
+    let mut stream = pin!(stream);
+    let mut hasher = HashTag::ViewStream.hasher::<V::Hasher>()?;
+    let mut count = 0u64;
+    while let Some(view) = stream.next().await {
+        let hash = view?.hash().await?;
+        hasher.update_with_bcs_bytes(&hash.as_ref())?;
+        count += 1;
+    }
+    hasher.update_with_bcs_bytes(&count)?;
+    Ok(hasher.finalize())
+}
+
+/// Encodes the output of a hasher as lowercase hexadecimal, without a `0x` prefix.
+pub fn hash_to_hex<H: Hasher>(output: &H::Output) -> String {
+    hex::encode(output)
+}
+
+/// Decodes the output of a hasher from its hexadecimal encoding, checking its length.
+pub fn hash_from_hex<H: Hasher>(hex: &str) -> Result<H::Output, ViewError> {
+    let bytes = hex::decode(hex).map_err(|error| ViewError::InvalidHexHash(error.to_string()))?;
+    H::output_from_bytes(&bytes).ok_or_else(|| {
+        ViewError::InvalidHexHash(format!("unexpected length of {} bytes", bytes.len()))
+    })
 }
 
 /// A [`View`] whose staged modifications can be saved in storage.
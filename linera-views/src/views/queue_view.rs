@@ -23,7 +23,10 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        empty_sentinel_hash, ClonableView, HashTag, HashableView, Hasher, View, ViewError,
+        MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -37,6 +40,9 @@ static QUEUE_VIEW_HASH_RUNTIME: LazyLock<HistogramVec> = LazyLock::new(|| {
     )
 });
 
+/// The default number of stored values read at once when hashing a `QueueView`.
+pub const HASH_CHUNK_SIZE: usize = 1000;
+
 /// Key tags to create the sub-keys of a `QueueView` on top of the base key.
 #[repr(u8)]
 enum KeyTag {
@@ -452,6 +458,45 @@ where
         self.load_all().await?;
         Ok(self.new_back_values.iter_mut())
     }
+
+    /// Computes the hash of the queue, reading the stored values by chunks of at most
+    /// `chunk_size` elements so that the whole queue is never loaded in memory at once.
+    /// The result is the same for every chunk size.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.push_back(34);
+    /// queue.push_back(37);
+    /// let hash = queue.hash_with_chunk_size(1).await.unwrap();
+    /// assert_eq!(hash, queue.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub async fn hash_with_chunk_size(&self, chunk_size: usize) -> Result<HasherOutput, ViewError> {
+        if self.count() == 0 {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
+        let mut hasher = HashTag::Queue.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bcs_sequence_length(self.count())?;
+        let chunk_size = chunk_size.max(1);
+        let mut start = self.stored_indices.end - self.stored_count();
+        while start < self.stored_indices.end {
+            let end = (start + chunk_size).min(self.stored_indices.end);
+            for value in self.read_context(start..end).await? {
+                hasher.update_with_bcs_bytes(&value)?;
+            }
+            start = end;
+        }
+        for value in &self.new_back_values {
+            hasher.update_with_bcs_bytes(value)?;
+        }
+        Ok(hasher.finalize())
+    }
 }
 
 impl<C, T> HashableView<C> for QueueView<C, T>
@@ -469,10 +514,7 @@ where
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = QUEUE_VIEW_HASH_RUNTIME.measure_latency();
-        let elements = self.elements().await?;
-        let mut hasher = sha3::Sha3_256::default();
-        hasher.update_with_bcs_bytes(&elements)?;
-        Ok(hasher.finalize())
+        self.hash_with_chunk_size(HASH_CHUNK_SIZE).await
     }
 }
 
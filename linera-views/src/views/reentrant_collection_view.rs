@@ -6,7 +6,6 @@ use std::sync::LazyLock;
 use std::{
     borrow::Borrow,
     collections::{btree_map, BTreeMap},
-    io::Write,
     marker::PhantomData,
     mem,
     sync::{Arc, Mutex},
@@ -27,8 +26,9 @@ use crate::{
     common::{CustomSerialize, HasherOutput, Update},
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
+    merkle,
     store::{KeyIterable, ReadableKeyValueStore as _},
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{ClonableView, HashTag, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
 
 #[cfg(with_metrics)]
@@ -1013,25 +1013,22 @@ where
     async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = REENTRANT_COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
         let keys = self.keys().await?;
-        let count = keys.len() as u32;
-        hasher.update_with_bcs_bytes(&count)?;
+        let mut leaves = Vec::with_capacity(keys.len());
         let cached_entries = self.cached_entries.get_mut().unwrap();
         for key in keys {
-            hasher.update_with_bytes(&key)?;
             let hash = if let Some(entry) = self.updates.get_mut(&key) {
                 let Update::Set(view) = entry else {
                     unreachable!();
                 };
                 let mut view = view
                     .try_write_arc()
-                    .ok_or_else(|| ViewError::TryLockError(key))?;
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
                 view.hash_mut().await?
             } else if let Some(view) = cached_entries.get_mut(&key) {
                 let mut view = view
                     .try_write_arc()
-                    .ok_or_else(|| ViewError::TryLockError(key))?;
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
                 view.hash_mut().await?
             } else {
                 let key = self
@@ -1042,18 +1039,16 @@ where
                 let mut view = W::load(context).await?;
                 view.hash_mut().await?
             };
-            hasher.write_all(hash.as_ref())?;
+            leaves.push(merkle::leaf_hash(&key, hash.as_ref())?);
         }
-        Ok(hasher.finalize())
+        self.hash_leaves(leaves)
     }
 
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = REENTRANT_COLLECTION_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
         let keys = self.keys().await?;
-        let count = keys.len() as u32;
-        hasher.update_with_bcs_bytes(&count)?;
+        let mut leaves = Vec::with_capacity(keys.len());
         let mut cached_entries_result = Vec::new();
         {
             let cached_entries = self.cached_entries.lock().unwrap();
@@ -1062,19 +1057,18 @@ where
             }
         }
         for (key, cached_entry) in keys.into_iter().zip(cached_entries_result) {
-            hasher.update_with_bytes(&key)?;
             let hash = if let Some(entry) = self.updates.get(&key) {
                 let Update::Set(view) = entry else {
                     unreachable!();
                 };
                 let view = view
                     .try_read_arc()
-                    .ok_or_else(|| ViewError::TryLockError(key))?;
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
                 view.hash().await?
             } else if let Some(view) = cached_entry {
                 let view = view
                     .try_read_arc()
-                    .ok_or_else(|| ViewError::TryLockError(key))?;
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
                 view.hash().await?
             } else {
                 let key = self
@@ -1085,8 +1079,25 @@ where
                 let view = W::load(context).await?;
                 view.hash().await?
             };
-            hasher.write_all(hash.as_ref())?;
+            leaves.push(merkle::leaf_hash(&key, hash.as_ref())?);
         }
+        self.hash_leaves(leaves)
+    }
+}
+
+impl<C, W> ReentrantByteCollectionView<C, W>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    /// Hashes the root of the Merkle tree whose leaves commit to the keys and the hashes
+    /// of the entries, in the order of the keys. This is the same hash as for a
+    /// [`crate::collection_view::ByteCollectionView`] with the same entries.
+    fn hash_leaves(&self, leaves: Vec<HasherOutput>) -> Result<HasherOutput, ViewError> {
+        let root = merkle::merkle_root(leaves)?;
+        let mut hasher =
+            HashTag::Collection.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bytes(&root)?;
         Ok(hasher.finalize())
     }
 }
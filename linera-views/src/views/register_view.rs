@@ -1,6 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::io::Write as _;
 #[cfg(with_metrics)]
 use std::sync::LazyLock;
 
@@ -19,7 +20,9 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError},
+    views::{
+        ClonableView, DynHasher, HashTag, HashableView, Hasher, MultiHashableView, View, ViewError,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -204,12 +207,73 @@ where
     fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = REGISTER_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
+        HashTag::Register
+            .keyed_hash_serializable::<sha3::Sha3_256, _>(self.context.hash_key(), self.get())
+    }
+
+    /// Computes the hash of the register chained to a previous hash, e.g. the chained hash
+    /// of the previous state of the register. The previous hash comes first, and a
+    /// different tag is used, so that a chained hash is never equal to the hash of a
+    /// register.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut register = RegisterView::<_, u32>::load(context).await.unwrap();
+    /// register.set(5);
+    /// let first = register.hash().await.unwrap();
+    /// register.set(6);
+    /// let second = register.hash_with_previous(&first).unwrap();
+    /// assert_ne!(second, register.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub fn hash_with_previous(
+        &self,
+        previous: &HasherOutput,
+    ) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = REGISTER_VIEW_HASH_RUNTIME.measure_latency();
+        let mut hasher =
+            HashTag::ChainedRegister.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
+        hasher.update_with_bytes(previous)?;
         hasher.update_with_bcs_bytes(self.get())?;
         Ok(hasher.finalize())
     }
+
+    /// Computes the hash of the register using the given hasher, which is reset first, with
+    /// the hash key of the context if any. The hasher is left in its initial state, so that
+    /// it can be reused for other registers.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut register = RegisterView::<_, u32>::load(context).await.unwrap();
+    /// register.set(5);
+    /// let mut hasher = sha3::Sha3_256::default();
+    /// let hash = register.hash_with(&mut hasher).unwrap();
+    /// assert_eq!(hash, register.hash().await.unwrap());
+    /// # })
+    /// ```
+    pub fn hash_with(
+        &self,
+        hasher: &mut sha3::Sha3_256,
+    ) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
+        #[cfg(with_metrics)]
+        let _hash_latency = REGISTER_VIEW_HASH_RUNTIME.measure_latency();
+        HashTag::Register.reset_hasher(hasher, self.context.hash_key())?;
+        hasher.update_with_bcs_bytes(self.get())?;
+        Ok(sha3::Digest::finalize_reset(hasher))
+    }
 }
 
+/// The hash of a register is the [`HashTag::Register`] tag followed by the BCS
+/// serialization of the value. In particular, the value of a `RegisterView<C, Option<T>>` is
+/// hashed as a presence byte, `0` for `None` and `1` for `Some`, followed by the value only
+/// if it is present: an absent value is never confused with a default one.
 impl<C, T> HashableView<C> for RegisterView<C, T>
 where
     C: Context + Send + Sync,
@@ -227,6 +291,28 @@ where
     }
 }
 
+impl<C, T> MultiHashableView<C> for RegisterView<C, T>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    T: Clone + Default + Send + Sync + Serialize + DeserializeOwned,
+{
+    async fn hash_multi(
+        &self,
+        hashers: &mut [&mut dyn DynHasher],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        let value = bcs::to_bytes(self.get())?;
+        hashers
+            .iter_mut()
+            .map(|hasher| {
+                HashTag::Register.reset_dyn_hasher(*hasher, self.context.hash_key())?;
+                hasher.write_all(&value)?;
+                Ok(hasher.finalize_bytes())
+            })
+            .collect()
+    }
+}
+
 /// Type wrapping `RegisterView` while memoizing the hash.
 pub type HashedRegisterView<C, T> =
     WrappedHashableContainerView<C, RegisterView<C, T>, HasherOutput>;
@@ -20,7 +20,7 @@ use crate::{
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
     store::{KeyIterable, ReadableKeyValueStore as _},
-    views::{ClonableView, HashableView, Hasher, View, ViewError},
+    views::{empty_sentinel_hash, ClonableView, HashTag, HashableView, Hasher, View, ViewError},
 };
 
 #[cfg(with_metrics)]
@@ -373,14 +373,19 @@ where
     async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = SET_VIEW_HASH_RUNTIME.measure_latency();
-        let mut hasher = sha3::Sha3_256::default();
+        let mut hasher = HashTag::Set.keyed_hasher::<sha3::Sha3_256>(self.context.hash_key())?;
         let mut count = 0u32;
         self.for_each_key(|key| {
             count += 1;
-            hasher.update_with_bytes(key)?;
+            hasher.update_with_bcs_bytes(&key)?;
             Ok(())
         })
         .await?;
+        if count == 0 {
+            if let Some(hash) = empty_sentinel_hash(&self.context) {
+                return Ok(hash);
+            }
+        }
         hasher.update_with_bcs_bytes(&count)?;
         Ok(hasher.finalize())
     }
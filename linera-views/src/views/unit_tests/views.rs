@@ -1,10 +1,17 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::VecDeque, fmt::Debug, marker::PhantomData};
+use std::{
+    collections::VecDeque,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
+use rand::Rng as _;
 use serde::{de::DeserializeOwned, Serialize};
 use test_case::test_case;
+use tokio_util::sync::CancellationToken;
 
 #[cfg(with_dynamodb)]
 use crate::dynamo_db::DynamoDbStore;
@@ -16,19 +23,26 @@ use crate::scylla_db::ScyllaDbStore;
 use crate::store::TestKeyValueStore;
 use crate::{
     batch::Batch,
-    context::{Context, MemoryContext},
+    collection_view::CollectionView,
+    common::HasherOutput,
+    context::{Context, MemoryContext, ViewContext},
+    log_view::LogView,
+    map_view::MapView,
+    memory::{create_test_memory_store, MemoryStore, MemoryStoreError},
+    merkle,
     queue_view::QueueView,
+    random::make_deterministic_rng,
     reentrant_collection_view::ReentrantCollectionView,
     register_view::{HashedRegisterView, RegisterView},
-    store::WritableKeyValueStore as _,
+    store::{ReadableKeyValueStore, WithError, WritableKeyValueStore},
     test_utils::test_views::{
         TestBucketQueueView, TestCollectionView, TestLogView, TestMapView, TestQueueView,
         TestRegisterView, TestSetView, TestView,
     },
-    views::{HashableView, View, ViewError},
+    views::{ClonableView as _, HashTag, HashableView, Hasher as _, View, ViewError},
 };
 #[cfg(any(with_rocksdb, with_scylladb, with_dynamodb))]
-use crate::{context::ViewContext, random::generate_test_namespace, store::AdminKeyValueStore};
+use crate::{random::generate_test_namespace, store::AdminKeyValueStore};
 
 #[tokio::test]
 async fn test_queue_operations_with_memory_context() -> Result<(), anyhow::Error> {
@@ -352,6 +366,385 @@ async fn test_clearing_of_cached_stored_hash() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Checks that the incrementally computed hash of a [`LogView`] matches a full
+/// recomputation, including after clearing and rolling back.
+#[tokio::test]
+async fn test_log_view_incremental_hash() -> anyhow::Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut log = LogView::<_, u32>::load(context.clone()).await?;
+    let empty_hash = log.hash().await?;
+
+    for batch in 0..4 {
+        for value in 0..5 {
+            log.push(batch * 5 + value);
+        }
+        let hash = log.hash().await?;
+        save_view(&context, &mut log).await?;
+        assert_eq!(log.hash().await?, hash);
+        let reloaded_log = LogView::<_, u32>::load(context.clone()).await?;
+        assert_eq!(reloaded_log.hash().await?, hash);
+    }
+
+    let hash = log.hash().await?;
+    log.push(100);
+    assert_ne!(log.hash().await?, hash);
+    log.rollback();
+    assert_eq!(log.hash().await?, hash);
+
+    log.clear();
+    assert_eq!(log.hash().await?, empty_hash);
+    save_view(&context, &mut log).await?;
+    assert_eq!(log.hash().await?, empty_hash);
+    log.push(7);
+    let reloaded_hash = {
+        let mut other_log = LogView::<_, u32>::load(context.clone()).await?;
+        other_log.push(7);
+        other_log.hash().await?
+    };
+    assert_eq!(log.hash().await?, reloaded_hash);
+
+    Ok(())
+}
+
+/// Checks that hashing the entries of a [`CollectionView`] concurrently gives the same result
+/// as hashing them one after the other.
+#[tokio::test]
+async fn test_collection_view_hash_with_concurrency() -> anyhow::Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = CollectionView::<_, u32, RegisterView<_, u32>>::load(context.clone()).await?;
+    for index in 0..50 {
+        view.load_entry_mut(&index).await?.set(index * 3);
+    }
+    save_view(&context, &mut view).await?;
+    for index in 40..60 {
+        view.load_entry_mut(&index).await?.set(index * 7);
+    }
+    view.remove_entry(&5)?;
+
+    let hash = view.hash().await?;
+    for concurrency in [0, 1, 2, 7, 16, 100] {
+        assert_eq!(view.hash_with_concurrency(concurrency).await?, hash);
+    }
+    Ok(())
+}
+
+/// Checks that [`CollectionView::entry_hashes`] localizes the changes to a collection.
+#[tokio::test]
+async fn test_collection_view_entry_hashes() -> anyhow::Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone()).await?;
+    for index in 0..5 {
+        view.load_entry_mut(&index).await?.set(u64::from(index));
+    }
+    save_view(&context, &mut view).await?;
+    let before = view.entry_hashes().await?;
+    assert_eq!(
+        before.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+        view.indices().await?
+    );
+
+    view.load_entry_mut(&3).await?.set(42);
+    let after = view.entry_hashes().await?;
+    assert_eq!(before.len(), after.len());
+    let changed = before
+        .iter()
+        .zip(&after)
+        .filter(|(before, after)| before != after)
+        .map(|(_, (index, _))| *index)
+        .collect::<Vec<_>>();
+    assert_eq!(changed, vec![3]);
+
+    view.rollback();
+    assert_eq!(view.entry_hashes().await?, before);
+    Ok(())
+}
+
+/// Checks that the incrementally maintained Merkle tree of a [`CollectionView`] always has
+/// the same root as a tree built from scratch, through random mutations, saves and rollbacks.
+#[tokio::test]
+async fn test_collection_view_incremental_hash() -> anyhow::Result<()> {
+    let mut rng = make_deterministic_rng();
+    let context = MemoryContext::new_for_testing(());
+    let mut view = CollectionView::<_, u32, RegisterView<_, u32>>::load(context.clone()).await?;
+    for _ in 0..200 {
+        match rng.gen_range(0..10) {
+            0..=5 => {
+                let index = rng.gen_range(0..30);
+                view.load_entry_mut(&index).await?.set(rng.gen());
+            }
+            6 | 7 => view.remove_entry(&rng.gen_range(0..30))?,
+            8 => save_view(&context, &mut view).await?,
+            _ => view.rollback(),
+        }
+        if rng.gen_bool(0.5) {
+            let hash = view.hash_mut().await?;
+            assert_eq!(hash, rebuild_collection_hash(&mut view).await?);
+            assert_eq!(view.hash().await?, hash);
+        }
+    }
+    view.clear();
+    assert_eq!(
+        view.hash().await?,
+        rebuild_collection_hash(&mut view).await?
+    );
+    view.rollback();
+    assert_eq!(
+        view.hash().await?,
+        rebuild_collection_hash(&mut view).await?
+    );
+    Ok(())
+}
+
+/// Computes the hash of a [`CollectionView`] by building the Merkle tree of its entries
+/// from scratch.
+async fn rebuild_collection_hash(
+    view: &mut CollectionView<MemoryContext<()>, u32, RegisterView<MemoryContext<()>, u32>>,
+) -> anyhow::Result<HasherOutput> {
+    let view = view.clone_unchecked()?;
+    let mut leaves = Vec::new();
+    for index in view.indices().await? {
+        let entry_hash = view.try_load_entry(&index).await?.unwrap().hash().await?;
+        leaves.push(merkle::leaf_hash(&bcs::to_bytes(&index)?, &entry_hash)?);
+    }
+    let mut hasher = HashTag::Collection.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&merkle::merkle_root(leaves)?)?;
+    Ok(hasher.finalize())
+}
+
+/// A store that returns the results of `find_key_values_by_prefix` in reverse order, unlike
+/// the other backends.
+#[derive(Clone)]
+struct UnsortedStore(MemoryStore);
+
+impl WithError for UnsortedStore {
+    type Error = MemoryStoreError;
+}
+
+impl ReadableKeyValueStore for UnsortedStore {
+    const MAX_KEY_SIZE: usize = MemoryStore::MAX_KEY_SIZE;
+    type Keys = Vec<Vec<u8>>;
+    type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+    fn max_stream_queries(&self) -> usize {
+        self.0.max_stream_queries()
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, MemoryStoreError> {
+        self.0.read_value_bytes(key).await
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, MemoryStoreError> {
+        self.0.contains_key(key).await
+    }
+
+    async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, MemoryStoreError> {
+        self.0.contains_keys(keys).await
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, MemoryStoreError> {
+        self.0.read_multi_values_bytes(keys).await
+    }
+
+    async fn find_keys_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        self.0.find_keys_by_prefix(key_prefix).await
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        let mut key_values = self.0.find_key_values_by_prefix(key_prefix).await?;
+        key_values.reverse();
+        Ok(key_values)
+    }
+}
+
+impl WritableKeyValueStore for UnsortedStore {
+    const MAX_VALUE_SIZE: usize = MemoryStore::MAX_VALUE_SIZE;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), MemoryStoreError> {
+        self.0.write_batch(batch).await
+    }
+
+    async fn clear_journal(&self) -> Result<(), MemoryStoreError> {
+        self.0.clear_journal().await
+    }
+}
+
+/// Checks that the hash of a [`MapView`] does not depend on the order in which the storage
+/// returns its entries.
+#[tokio::test]
+async fn test_map_view_hash_with_unsorted_storage() -> anyhow::Result<()> {
+    let store = UnsortedStore(create_test_memory_store());
+    let context = ViewContext::create_root_context(store, ()).await?;
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    let reference_context = MemoryContext::new_for_testing(());
+    let mut reference_map = MapView::<_, u32, String>::load(reference_context.clone()).await?;
+    for index in [7, 300, 1, 65536, 42] {
+        map.insert(&index, index.to_string())?;
+        reference_map.insert(&index, index.to_string())?;
+    }
+    save_view(&context, &mut map).await?;
+    save_view(&reference_context, &mut reference_map).await?;
+
+    let map = MapView::<_, u32, String>::load(context).await?;
+    assert_eq!(map.hash().await?, reference_map.hash().await?);
+    Ok(())
+}
+
+/// A [`MemoryStore`] that cancels a token once a given number of values were read.
+struct CancellingStore {
+    store: MemoryStore,
+    token: CancellationToken,
+    /// The number of calls to `read_multi_values_bytes`.
+    reads: AtomicUsize,
+    /// The value of `reads` at which the token is cancelled.
+    cancel_at: AtomicUsize,
+}
+
+impl WithError for CancellingStore {
+    type Error = MemoryStoreError;
+}
+
+impl ReadableKeyValueStore for CancellingStore {
+    const MAX_KEY_SIZE: usize = MemoryStore::MAX_KEY_SIZE;
+    type Keys = Vec<Vec<u8>>;
+    type KeyValues = Vec<(Vec<u8>, Vec<u8>)>;
+
+    fn max_stream_queries(&self) -> usize {
+        self.store.max_stream_queries()
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, MemoryStoreError> {
+        self.store.read_value_bytes(key).await
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, MemoryStoreError> {
+        self.store.contains_key(key).await
+    }
+
+    async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, MemoryStoreError> {
+        self.store.contains_keys(keys).await
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, MemoryStoreError> {
+        let reads = self.reads.fetch_add(1, Ordering::Relaxed) + 1;
+        if reads == self.cancel_at.load(Ordering::Relaxed) {
+            self.token.cancel();
+        }
+        self.store.read_multi_values_bytes(keys).await
+    }
+
+    async fn find_keys_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        self.store.find_keys_by_prefix(key_prefix).await
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        self.store.find_key_values_by_prefix(key_prefix).await
+    }
+}
+
+impl WritableKeyValueStore for CancellingStore {
+    const MAX_VALUE_SIZE: usize = MemoryStore::MAX_VALUE_SIZE;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), MemoryStoreError> {
+        self.store.write_batch(batch).await
+    }
+
+    async fn clear_journal(&self) -> Result<(), MemoryStoreError> {
+        self.store.clear_journal().await
+    }
+}
+
+/// Checks that hashing a [`CollectionView`] stops as soon as the token is cancelled, and
+/// that the hash is still correct afterwards.
+#[tokio::test]
+async fn test_collection_view_hash_with_cancel() -> anyhow::Result<()> {
+    type TestCollection<C> = CollectionView<C, u32, RegisterView<C, u64>>;
+
+    let token = CancellationToken::new();
+    let store = CancellingStore {
+        store: create_test_memory_store(),
+        token: token.clone(),
+        reads: AtomicUsize::new(0),
+        cancel_at: AtomicUsize::new(0),
+    };
+    let context = ViewContext::create_root_context(store, ()).await?;
+    let mut collection = TestCollection::load(context.clone()).await?;
+    for index in 0..1000 {
+        collection
+            .load_entry_mut(&index)
+            .await?
+            .set(u64::from(index));
+    }
+    save_view(&context, &mut collection).await?;
+
+    // Loading each entry reads the storage once: cancel after loading 10 of them.
+    let mut collection = TestCollection::load(context.clone()).await?;
+    let reads = context.store().reads.load(Ordering::Relaxed);
+    context
+        .store()
+        .cancel_at
+        .store(reads + 10, Ordering::Relaxed);
+    assert!(matches!(
+        collection.hash_with_cancel(token).await,
+        Err(ViewError::Cancelled)
+    ));
+    assert_eq!(context.store().reads.load(Ordering::Relaxed), reads + 10);
+
+    let expected_hash = TestCollection::load(context.clone()).await?.hash().await?;
+    assert_eq!(
+        collection
+            .hash_with_cancel(CancellationToken::new())
+            .await?,
+        expected_hash
+    );
+    assert_eq!(collection.hash_mut().await?, expected_hash);
+    Ok(())
+}
+
+/// Checks that hashing a [`QueueView`] by chunks gives the same result as hashing all its
+/// elements at once.
+#[tokio::test]
+async fn test_queue_view_hash_with_chunk_size() -> anyhow::Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut queue = QueueView::<_, u64>::load(context.clone()).await?;
+    for value in 0..25 {
+        queue.push_back(value);
+    }
+    save_view(&context, &mut queue).await?;
+    for _ in 0..3 {
+        queue.delete_front();
+    }
+    for value in 25..30 {
+        queue.push_back(value);
+    }
+
+    let mut hasher = HashTag::Queue.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bcs_bytes(&queue.elements().await?)?;
+    let expected_hash = hasher.finalize();
+    assert_eq!(queue.hash().await?, expected_hash);
+    for chunk_size in [0, 1, 4, 10, 22, 100] {
+        assert_eq!(queue.hash_with_chunk_size(chunk_size).await?, expected_hash);
+    }
+    Ok(())
+}
+
 /// Checks if a [`ReentrantCollectionView`] doesn't have pending changes after loading its
 /// entries.
 #[tokio::test]
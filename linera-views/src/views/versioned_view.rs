@@ -0,0 +1,145 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::{Deref, DerefMut};
+
+use crate::{
+    batch::Batch,
+    context::Context,
+    views::{ClonableView, HashTag, HashableView, Hasher, View, ViewError},
+};
+
+/// A view whose hash is scoped to the schema version `VERSION` of the inner view, so that
+/// the hashes of two versions never coincide, even for the same stored data. The inner view
+/// is stored as is: wrapping a view or changing its version does not change the storage.
+/// ```rust
+/// # tokio_test::block_on(async {
+/// # use linera_views::context::MemoryContext;
+/// # use linera_views::register_view::RegisterView;
+/// # use linera_views::versioned_view::Versioned;
+/// # use linera_views::views::{HashableView, View};
+/// # let context = MemoryContext::new_for_testing(());
+/// let mut register = Versioned::<RegisterView<_, u64>, 2>::load(context).await.unwrap();
+/// register.set(7);
+/// assert_ne!(register.hash().await.unwrap(), register.inner().hash().await.unwrap());
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct Versioned<W, const VERSION: u32> {
+    inner: W,
+}
+
+impl<W, const VERSION: u32> Versioned<W, VERSION> {
+    /// Obtains a reference to the inner view.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner view.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<C, W, const VERSION: u32> View<C> for Versioned<W, VERSION>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: View<C> + Send + Sync,
+{
+    const NUM_INIT_KEYS: usize = W::NUM_INIT_KEYS;
+
+    fn context(&self) -> &C {
+        self.inner.context()
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        W::pre_load(context)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let inner = W::post_load(context, values)?;
+        Ok(Self { inner })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let inner = W::load(context).await?;
+        Ok(Self { inner })
+    }
+
+    fn rollback(&mut self) {
+        self.inner.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.inner.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.inner.flush(batch)
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+impl<C, W, const VERSION: u32> ClonableView<C> for Versioned<W, VERSION>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: ClonableView<C> + Send + Sync,
+{
+    fn clone_unchecked(&mut self) -> Result<Self, ViewError> {
+        Ok(Versioned {
+            inner: self.inner.clone_unchecked()?,
+        })
+    }
+}
+
+impl<W, const VERSION: u32> Versioned<W, VERSION> {
+    /// Hashes the version followed by the hash of the inner view, keyed with `key` if there
+    /// is one.
+    fn hash_version<H: Hasher>(
+        key: Option<&[u8; 32]>,
+        inner_hash: H::Output,
+    ) -> Result<H::Output, ViewError> {
+        let mut hasher = HashTag::Versioned.keyed_hasher::<H>(key)?;
+        hasher.update_with_bcs_bytes(&VERSION)?;
+        hasher.update_with_bcs_bytes(&inner_hash.as_ref())?;
+        Ok(hasher.finalize())
+    }
+}
+
+impl<C, W, const VERSION: u32> HashableView<C> for Versioned<W, VERSION>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+    W: HashableView<C> + Send + Sync,
+{
+    type Hasher = W::Hasher;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        let inner_hash = self.inner.hash_mut().await?;
+        Self::hash_version::<W::Hasher>(self.inner.context().hash_key(), inner_hash)
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        let inner_hash = self.inner.hash().await?;
+        Self::hash_version::<W::Hasher>(self.inner.context().hash_key(), inner_hash)
+    }
+}
+
+impl<W, const VERSION: u32> Deref for Versioned<W, VERSION> {
+    type Target = W;
+
+    fn deref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W, const VERSION: u32> DerefMut for Versioned<W, VERSION> {
+    fn deref_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the hashes of the views only depend on their contents: not on the history
+//! of the view, nor on whether the contents are in memory or in storage, nor on the
+//! platform. The golden digests below must only change together with the hashing format.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use linera_views::{
+    collection_view::CollectionView,
+    common::HasherOutput,
+    context::{Context, MemoryContext},
+    map_view::MapView,
+    queue_view::QueueView,
+    random::make_deterministic_rng,
+    register_view::RegisterView,
+    views::{
+        hash_from_hex, CryptoHashRootView, CryptoHashView, HashableView, RootView, View, ViewError,
+    },
+};
+use rand::{seq::SliceRandom as _, Rng};
+
+#[derive(CryptoHashRootView)]
+struct HashedStateView<C> {
+    pub register: RegisterView<C, u64>,
+    pub map: MapView<C, u32, String>,
+    pub queue: QueueView<C, u64>,
+    pub collection: CollectionView<C, u16, RegisterView<C, u64>>,
+}
+
+/// The contents of a [`HashedStateView`].
+#[derive(Debug)]
+struct Contents {
+    register: u64,
+    map: BTreeMap<u32, String>,
+    queue: Vec<u64>,
+    collection: BTreeMap<u16, u64>,
+}
+
+impl Contents {
+    fn random(rng: &mut impl Rng) -> Self {
+        let map_len = rng.gen_range(0..20);
+        let queue_len = rng.gen_range(0..20);
+        let collection_len = rng.gen_range(0..20);
+        Contents {
+            register: rng.gen(),
+            map: (0..map_len)
+                .map(|_| (rng.gen(), rng.gen::<u64>().to_string()))
+                .collect(),
+            queue: (0..queue_len).map(|_| rng.gen()).collect(),
+            collection: (0..collection_len)
+                .map(|_| (rng.gen(), rng.gen()))
+                .collect(),
+        }
+    }
+
+    /// The contents whose digests are recorded in [`GOLDEN_DIGESTS`].
+    fn golden() -> Self {
+        Contents {
+            register: 0x0102_0304_0506_0708,
+            map: [
+                (1, "one".to_string()),
+                (256, "two hundred fifty-six".to_string()),
+                (65536, "a".to_string()),
+            ]
+            .into(),
+            queue: vec![1, u64::MAX, 0x0102_0304_0506_0708],
+            collection: [(1, 10), (300, 20)].into(),
+        }
+    }
+}
+
+/// The digests of the register, map, queue and collection of [`Contents::golden`].
+const GOLDEN_DIGESTS: [&str; 4] = [
+    "815fe605e9ae93eeca90af602973380244e060602c73912a662c9539c38f705f",
+    "20e73b0eb659c29ae43d4622eae87b6ba97265018144cffb3394060eab57a81d",
+    "0e9637e5238a279431dd5cbec26b57e2284016f2b796c4775b2ce3e2f50333d8",
+    "464b58291eb00645cdc17f804ea920a044223236373680ef7fb80228e6302b14",
+];
+
+impl<C> HashedStateView<C>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    /// Replaces the contents of the view, writing the entries in the given order.
+    async fn write(&mut self, contents: &Contents, rng: &mut impl Rng) -> Result<()> {
+        self.clear();
+        self.register.set(contents.register);
+        let mut map_entries = contents.map.iter().collect::<Vec<_>>();
+        map_entries.shuffle(rng);
+        for (index, value) in map_entries {
+            self.map.insert(index, value.clone())?;
+        }
+        for value in &contents.queue {
+            self.queue.push_back(*value);
+        }
+        let mut collection_entries = contents.collection.iter().collect::<Vec<_>>();
+        collection_entries.shuffle(rng);
+        for (index, value) in collection_entries {
+            self.collection.load_entry_mut(index).await?.set(*value);
+        }
+        Ok(())
+    }
+
+    async fn digests(&self) -> Result<[HasherOutput; 4]> {
+        Ok([
+            self.register.hash().await?,
+            self.map.hash().await?,
+            self.queue.hash().await?,
+            self.collection.hash().await?,
+        ])
+    }
+}
+
+/// Writes `contents` to a new view, checking that the hashes are the same before saving,
+/// after saving and after reloading the view. Returns the digests.
+async fn check_round_trip(contents: &Contents, rng: &mut impl Rng) -> Result<[HasherOutput; 4]> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = HashedStateView::load(context.clone()).await?;
+    // Start from different contents, so that the history of the view differs.
+    view.write(&Contents::random(rng), rng).await?;
+    view.save().await?;
+    view.write(contents, rng).await?;
+    let digests = view.digests().await?;
+    let crypto_hash = view.crypto_hash_mut().await?;
+    view.save().await?;
+    assert_eq!(view.digests().await?, digests);
+
+    let mut view = HashedStateView::load(context).await?;
+    assert_eq!(view.digests().await?, digests);
+    assert_eq!(view.crypto_hash_mut().await?, crypto_hash);
+    Ok(digests)
+}
+
+#[tokio::test]
+async fn check_hashes_of_random_views_are_stable() -> Result<()> {
+    let mut rng = make_deterministic_rng();
+    for _ in 0..20 {
+        let contents = Contents::random(&mut rng);
+        let digests = check_round_trip(&contents, &mut rng).await?;
+        assert_eq!(check_round_trip(&contents, &mut rng).await?, digests);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_golden_digests() -> Result<()> {
+    let mut rng = make_deterministic_rng();
+    let digests = check_round_trip(&Contents::golden(), &mut rng).await?;
+    for (digest, golden) in digests.iter().zip(GOLDEN_DIGESTS) {
+        assert_eq!(*digest, hash_from_hex::<sha3::Sha3_256>(golden)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that the hashing prelude is enough to implement and hash a view.
+
+use linera_views::hash::prelude::*;
+
+/// A view with a fixed label, hashed with the given hasher.
+struct LabelView<C, H> {
+    context: C,
+    label: &'static str,
+    _hasher: std::marker::PhantomData<H>,
+}
+
+impl<C, H> View<C> for LabelView<C, H>
+where
+    C: Context + Send + Sync,
+    H: Send + Sync,
+{
+    const NUM_INIT_KEYS: usize = 0;
+
+    fn context(&self) -> &C {
+        &self.context
+    }
+
+    fn pre_load(_context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        Ok(Vec::new())
+    }
+
+    fn post_load(context: C, _values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        Ok(LabelView {
+            context,
+            label: "label",
+            _hasher: std::marker::PhantomData,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        Self::post_load(context, &[])
+    }
+
+    fn rollback(&mut self) {}
+
+    async fn has_pending_changes(&self) -> bool {
+        false
+    }
+
+    fn clear(&mut self) {}
+
+    fn flush(&mut self, _batch: &mut Batch) -> Result<bool, ViewError> {
+        Ok(false)
+    }
+}
+
+impl<C, H> HashableView<C> for LabelView<C, H>
+where
+    C: Context + Send + Sync,
+    H: Hasher,
+{
+    type Hasher = H;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.hash().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        HashTag::Register.keyed_hash_serializable::<H, _>(self.context.hash_key(), &self.label)
+    }
+}
+
+async fn check_label_hash<H: Hasher>() -> Result<(), ViewError> {
+    let context = linera_views::context::MemoryContext::new_for_testing(());
+    let view = LabelView::<_, H>::load(context).await?;
+    let hash = view.hash().await?;
+    assert_eq!(hash, HashTag::Register.hash_serializable::<H, _>(&"label")?);
+    assert_eq!(hash_view(&view).await.map_err(ViewError::from)?, hash);
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_hash_with_prelude() -> Result<(), ViewError> {
+    check_label_hash::<Sha3_256>().await?;
+    check_label_hash::<Keccak256>().await?;
+    check_label_hash::<Sha256>().await?;
+    check_label_hash::<Blake3>().await?;
+    check_label_hash::<Xxh3Hasher>().await?;
+    let context = linera_views::context::MemoryContext::new_for_testing(());
+    let view = LabelView::<_, Sha3_256>::load(context).await?;
+    let hash: HasherOutput = view.hash().await?;
+    assert_eq!(
+        ViewHash::try_from(hash.as_slice())?.as_ref(),
+        hash.as_slice()
+    );
+    Ok(())
+}
@@ -1,15 +1,37 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{
+    collections::{BTreeSet, HashSet},
+    io::Write as _,
+};
+
 use anyhow::Result;
 use linera_views::{
+    batch::Batch,
+    bucket_queue_view::BucketQueueView,
+    collection_view::{ByteCollectionView, CollectionView},
     common::HasherOutput,
-    context::MemoryContext,
+    context::{Context, MemoryContext},
+    hash::{CanonicalFloat, HashError, ViewHash},
     hashable_wrapper::WrappedHashableContainerView,
+    log_view::{HashProgress, HashState, LogView, HASH_CHUNK_SIZE},
+    map_view::{ByteMapView, MapView},
+    merkle,
+    queue_view::QueueView,
+    random::make_deterministic_rng,
+    reentrant_collection_view::ReentrantCollectionView,
     register_view::{HashedRegisterView, RegisterView},
-    views::{HashableView, View},
+    set_view::{ByteSetView, SetView},
+    store::WritableKeyValueStore as _,
+    versioned_view::Versioned,
+    views::{
+        combine_hashes, hash_from_hex, hash_to_hex, hash_view_stream, BlockBufferedHasher, HashTag,
+        HashableView, Hasher, MultiHashableView, View, ViewError, Xxh3Hasher, EMPTY_SENTINEL_HASH,
+    },
 };
 use linera_views_derive::CryptoHashRootView;
+use rand::Rng as _;
 
 #[derive(CryptoHashRootView)]
 struct TestType<C> {
@@ -41,3 +63,1530 @@ async fn check_hashable_hash() -> Result<()> {
     assert_eq!(hash0, view.hash().await?);
     Ok(())
 }
+
+fn hash_register<H: Hasher>(view: &RegisterView<MemoryContext<()>, u64>) -> Result<H::Output> {
+    Ok(HashTag::Register.hash_serializable::<H, _>(view.get())?)
+}
+
+#[tokio::test]
+async fn check_blake3_hash_is_deterministic() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = RegisterView::<_, u64>::load(context).await?;
+    view.set(42);
+    let sha3_hash = hash_register::<sha3::Sha3_256>(&view)?;
+    let blake3_hash = hash_register::<blake3::Hasher>(&view)?;
+    assert_eq!(sha3_hash, view.hash().await?);
+    assert_eq!(blake3_hash.len(), 32);
+    assert_ne!(sha3_hash.as_slice(), blake3_hash.as_slice());
+    for _ in 0..3 {
+        assert_eq!(sha3_hash, hash_register::<sha3::Sha3_256>(&view)?);
+        assert_eq!(blake3_hash, hash_register::<blake3::Hasher>(&view)?);
+    }
+    Ok(())
+}
+
+/// Views with the same contents have unrelated hashes under different keys.
+#[tokio::test]
+async fn check_keyed_register_hashes() -> Result<()> {
+    let mut hashes = Vec::new();
+    for hash_key in [None, Some([1; 32]), Some([2; 32]), Some([1; 32])] {
+        let mut context = MemoryContext::new_for_testing(());
+        if let Some(hash_key) = hash_key {
+            context = context.with_hash_key(hash_key);
+        }
+        let mut view = RegisterView::<_, u64>::load(context).await?;
+        view.set(42);
+        hashes.push(view.hash().await?);
+    }
+    assert_ne!(hashes[0], hashes[1]);
+    assert_ne!(hashes[1], hashes[2]);
+    assert_eq!(hashes[1], hashes[3]);
+    Ok(())
+}
+
+/// Builds the chain of hashes of the successive values of a register, starting from the
+/// plain hash of the first value.
+async fn chained_register_hashes(values: &[u64]) -> Result<Vec<HasherOutput>> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context).await?;
+    let mut hashes = Vec::<HasherOutput>::new();
+    for value in values {
+        register.set(*value);
+        let hash = match hashes.last() {
+            None => register.hash().await?,
+            Some(previous) => register.hash_with_previous(previous)?,
+        };
+        hashes.push(hash);
+    }
+    Ok(hashes)
+}
+
+#[tokio::test]
+async fn check_chained_register_hashes() -> Result<()> {
+    let hashes = chained_register_hashes(&[1, 2, 3]).await?;
+    assert_eq!(chained_register_hashes(&[1, 2, 3]).await?, hashes);
+
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context).await?;
+    register.set(2);
+    assert_ne!(hashes[1], register.hash().await?);
+
+    // Altering a link changes its hash and the hashes of all the following links.
+    for changed in 0..3 {
+        let mut values = [1, 2, 3];
+        values[changed] += 10;
+        let altered_hashes = chained_register_hashes(&values).await?;
+        for (index, (altered_hash, hash)) in altered_hashes.iter().zip(&hashes).enumerate() {
+            assert_eq!(altered_hash == hash, index < changed);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_combined_hashes() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context.clone_with_base_key(vec![0])).await?;
+    let mut map = MapView::<_, u32, String>::load(context.clone_with_base_key(vec![1])).await?;
+    register.set(7);
+    map.insert(&1, "one".to_string())?;
+    map.insert(&2, "two".to_string())?;
+    let hashes = [register.hash().await?, map.hash().await?];
+    let combined = combine_hashes::<sha3::Sha3_256, _>(&hashes)?;
+
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bcs_bytes(&hashes.iter().map(AsRef::as_ref).collect::<Vec<&[u8]>>())?;
+    assert_eq!(combined, hasher.finalize());
+
+    let mut batch = Batch::new();
+    register.flush(&mut batch)?;
+    map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    let register = RegisterView::<_, u64>::load(context.clone_with_base_key(vec![0])).await?;
+    let map = MapView::<_, u32, String>::load(context.clone_with_base_key(vec![1])).await?;
+    let hashes = [register.hash().await?, map.hash().await?];
+    assert_eq!(combine_hashes::<sha3::Sha3_256, _>(&hashes)?, combined);
+
+    let [first, second] = hashes;
+    assert_ne!(
+        combine_hashes::<sha3::Sha3_256, _>([second, first])?,
+        combined
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_optional_register_hash() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, Option<u64>>::load(context).await?;
+    let none_hash = register.hash().await?;
+    let mut hasher = HashTag::Register.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&[0])?;
+    assert_eq!(none_hash, hasher.finalize());
+
+    register.set(Some(u64::default()));
+    let some_hash = register.hash().await?;
+    let mut hasher = HashTag::Register.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&[1])?;
+    hasher.update_with_bytes(&0u64.to_le_bytes())?;
+    assert_eq!(some_hash, hasher.finalize());
+    assert_ne!(none_hash, some_hash);
+    Ok(())
+}
+
+#[test]
+fn check_sha256_hasher() -> Result<()> {
+    // A fixed-size array is encoded by BCS without a length, so this is the "abc" test
+    // vector of FIPS 180-2.
+    let mut hasher = sha2::Sha256::default();
+    hasher.update_with_bcs_bytes(b"abc")?;
+    assert_eq!(
+        hash_to_hex::<sha2::Sha256>(&hasher.finalize()),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    // Successive values are hashed as the concatenation of their BCS encodings: here the
+    // length of an empty vector followed by the little-endian encoding of `42u64`.
+    let mut hasher = sha2::Sha256::default();
+    hasher.update_with_bcs_bytes(&Vec::<u64>::new())?;
+    hasher.update_with_bcs_bytes(&42u64)?;
+    assert_eq!(
+        hash_to_hex::<sha2::Sha256>(&hasher.finalize()),
+        "fac1e562906db920dc49ddea007cf6e971fc3b0c53e74581a3a618131a4b4d30"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_keccak256_hash_of_register() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = RegisterView::<_, u64>::load(context).await?;
+    view.set(42);
+    let hash = hash_register::<sha3::Keccak256>(&view)?;
+    let hex = hash
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    // Keccak-256 of the `RegisterView` tag followed by the little-endian encoding of `42u64`,
+    // as computed by EVM's `keccak256`.
+    assert_eq!(
+        hex,
+        "8ff533019cdaafe57501f9277b14999905811d43362ea543b9450b26a03f0184"
+    );
+    Ok(())
+}
+
+/// The hash of a collection is the root of a Merkle tree whose leaves length-prefix the
+/// keys and sub-view hashes, so that two different collections can never alias.
+#[tokio::test]
+async fn check_collection_hash_frames_keys_and_entries() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = ByteCollectionView::<_, RegisterView<_, u32>>::load(context).await?;
+    view.load_entry_mut(&[0]).await?.set(1);
+    view.load_entry_mut(&[0, 1]).await?.set(2);
+
+    let mut leaves = Vec::new();
+    for key in [vec![0], vec![0, 1]] {
+        let entry_hash = view.try_load_entry(&key).await?.unwrap().hash().await?;
+        leaves.push(merkle::leaf_hash(&key, &entry_hash)?);
+    }
+    let mut hasher = HashTag::Collection.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&merkle::merkle_root(leaves)?)?;
+    assert_eq!(view.hash().await?, hasher.finalize());
+    Ok(())
+}
+
+/// A reentrant collection is hashed like a collection with the same entries.
+#[tokio::test]
+async fn check_reentrant_collection_hash_matches_collection() -> Result<()> {
+    let context = MemoryContext::new_for_testing(()).with_hash_key([3; 32]);
+    let mut view =
+        CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![0]))
+            .await?;
+    let mut reentrant_view = ReentrantCollectionView::<_, u32, RegisterView<_, u64>>::load(
+        context.clone_with_base_key(vec![1]),
+    )
+    .await?;
+    for index in [3u32, 1, 200, 7] {
+        view.load_entry_mut(&index).await?.set(u64::from(index) * 5);
+        reentrant_view
+            .try_load_entry_mut(&index)
+            .await?
+            .set(u64::from(index) * 5);
+    }
+    assert_eq!(reentrant_view.hash().await?, view.hash().await?);
+    assert_eq!(reentrant_view.hash_mut().await?, view.hash_mut().await?);
+    Ok(())
+}
+
+/// A register counting the calls to `hash_mut`, to check that containers hash their
+/// entries mutably when they are hashed mutably.
+struct CountingRegister<C> {
+    inner: RegisterView<C, u64>,
+    hash_mut_calls: usize,
+}
+
+impl<C> View<C> for CountingRegister<C>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    const NUM_INIT_KEYS: usize = RegisterView::<C, u64>::NUM_INIT_KEYS;
+
+    fn context(&self) -> &C {
+        self.inner.context()
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        RegisterView::<C, u64>::pre_load(context)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let inner = RegisterView::post_load(context, values)?;
+        Ok(Self {
+            inner,
+            hash_mut_calls: 0,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let inner = RegisterView::load(context).await?;
+        Ok(Self {
+            inner,
+            hash_mut_calls: 0,
+        })
+    }
+
+    fn rollback(&mut self) {
+        self.inner.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.inner.has_pending_changes().await
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.inner.flush(batch)
+    }
+}
+
+impl<C> HashableView<C> for CountingRegister<C>
+where
+    C: Context + Send + Sync,
+    ViewError: From<C::Error>,
+{
+    type Hasher = sha3::Sha3_256;
+
+    async fn hash_mut(&mut self) -> Result<HasherOutput, ViewError> {
+        self.hash_mut_calls += 1;
+        self.inner.hash().await
+    }
+
+    async fn hash(&self) -> Result<HasherOutput, ViewError> {
+        self.inner.hash().await
+    }
+}
+
+#[tokio::test]
+async fn check_collection_hash_mut_hashes_entries_mutably() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = CollectionView::<_, u32, CountingRegister<_>>::load(context).await?;
+    for index in 0..4u32 {
+        view.load_entry_mut(&index)
+            .await?
+            .inner
+            .set(u64::from(index));
+    }
+    let hash = view.hash().await?;
+    for index in 0..4u32 {
+        assert_eq!(view.load_entry_mut(&index).await?.hash_mut_calls, 0);
+    }
+    assert_eq!(view.hash_mut().await?, hash);
+    assert_eq!(view.hash_with_concurrency(3).await?, hash);
+    for index in 0..4u32 {
+        assert_eq!(view.load_entry_mut(&index).await?.hash_mut_calls, 2);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_collection_diff() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view =
+        CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![0]))
+            .await?;
+    for index in 0..10u32 {
+        view.load_entry_mut(&index).await?.set(u64::from(index));
+    }
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    let mut other =
+        CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![0]))
+            .await?;
+
+    let (indices, empty_digest) = view.diff(&other).await?;
+    assert!(indices.is_empty());
+
+    other.load_entry_mut(&2).await?.set(20);
+    other.load_entry_mut(&7).await?.set(70);
+    other.remove_entry(&4)?;
+    other.load_entry_mut(&12).await?.set(12);
+    // Setting an entry to its current value is not a change.
+    other.load_entry_mut(&5).await?.set(5);
+    let (indices, digest) = view.diff(&other).await?;
+    assert_eq!(indices, BTreeSet::from([2, 4, 7, 12]));
+    assert_ne!(digest, empty_digest);
+    assert_eq!(view.diff(&other).await?, (indices.clone(), digest));
+
+    let mut hasher = HashTag::CollectionDiff.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bcs_sequence_length(indices.len())?;
+    for index in &indices {
+        let hash = match view.try_load_entry(index).await? {
+            Some(entry) => Some(entry.hash().await?),
+            None => None,
+        };
+        let other_hash = match other.try_load_entry(index).await? {
+            Some(entry) => Some(entry.hash().await?),
+            None => None,
+        };
+        hasher.update_with_bcs_bytes(&bcs::to_bytes(index)?)?;
+        hasher.update_with_bcs_bytes(&hash)?;
+        hasher.update_with_bcs_bytes(&other_hash)?;
+    }
+    assert_eq!(digest, hasher.finalize());
+
+    // The same changes give the same differences, in the other direction.
+    let (reverse_indices, reverse_digest) = other.diff(&view).await?;
+    assert_eq!(reverse_indices, indices);
+    assert_ne!(reverse_digest, digest);
+    Ok(())
+}
+
+/// Builds a map of maps from the given entries, inserted in order.
+async fn make_nested_map(
+    context: MemoryContext<()>,
+    entries: &[(u32, u32, String)],
+) -> Result<CollectionView<MemoryContext<()>, u32, MapView<MemoryContext<()>, u32, String>>> {
+    let mut view = CollectionView::load(context).await?;
+    for (outer, inner, value) in entries {
+        let map: &mut MapView<_, u32, String> = view.load_entry_mut(outer).await?;
+        map.insert(inner, value.clone())?;
+    }
+    Ok(view)
+}
+
+/// Nested maps are collections of maps, whose hash does not depend on the order of the
+/// insertions at either level.
+#[tokio::test]
+async fn check_nested_map_hash() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let entries = (0..4u32)
+        .flat_map(|outer| (0..3u32).map(move |inner| (outer, inner, format!("{outer}-{inner}"))))
+        .collect::<Vec<_>>();
+    let mut reversed_entries = entries.clone();
+    reversed_entries.reverse();
+    let view = make_nested_map(context.clone_with_base_key(vec![0]), &entries).await?;
+    let other_view =
+        make_nested_map(context.clone_with_base_key(vec![1]), &reversed_entries).await?;
+    assert_eq!(view.hash().await?, other_view.hash().await?);
+
+    // The hash commits to the hashes of the inner maps, in the order of the outer keys.
+    let mut leaves = Vec::new();
+    for outer in 0..4u32 {
+        let map = view.try_load_entry(&outer).await?.unwrap();
+        leaves.push(merkle::leaf_hash(
+            &bcs::to_bytes(&outer)?,
+            map.hash().await?.as_ref(),
+        )?);
+    }
+    let mut hasher = HashTag::Collection.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&merkle::merkle_root(leaves)?)?;
+    assert_eq!(view.hash().await?, hasher.finalize());
+
+    let mut changed_entries = entries.clone();
+    changed_entries[5].2 = "changed".to_string();
+    let changed_view =
+        make_nested_map(context.clone_with_base_key(vec![2]), &changed_entries).await?;
+    assert_ne!(view.hash().await?, changed_view.hash().await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_collection_hash_subset() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view =
+        CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![0]))
+            .await?;
+    for index in 0..10u32 {
+        view.load_entry_mut(&index).await?.set(u64::from(index));
+    }
+    let mut batch = Batch::new();
+    view.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    view.load_entry_mut(&3).await?.set(30);
+
+    // Each shard is assigned a range of indices, some of them not in the collection.
+    let first = view.hash_subset(&(0..6).collect::<Vec<u32>>()).await?;
+    let second = view.hash_subset(&(6..15).collect::<Vec<u32>>()).await?;
+    assert_eq!((first.len(), second.len()), (6, 4));
+    let hash = view.hash().await?;
+    assert_eq!(first.clone().fold(second.clone())?.hash()?, hash);
+    assert_eq!(second.clone().fold(first.clone())?.hash()?, hash);
+    assert_ne!(first.hash()?, hash);
+
+    // A single subset with all the indices, in any order, is the whole collection.
+    let all = view.hash_subset(&[9, 3, 0, 1, 2, 4, 5, 6, 7, 8]).await?;
+    assert_eq!(all.hash()?, hash);
+
+    let overlapping = view.hash_subset(&[5, 6]).await?;
+    assert!(matches!(
+        first.clone().fold(overlapping),
+        Err(ViewError::OverlappingSubsets)
+    ));
+
+    // Subsets of a collection under another hash key cannot be folded in.
+    let keyed_context = context.with_hash_key([7; 32]);
+    let mut keyed_view = CollectionView::<_, u32, RegisterView<_, u64>>::load(
+        keyed_context.clone_with_base_key(vec![1]),
+    )
+    .await?;
+    keyed_view.load_entry_mut(&7).await?.set(7);
+    let keyed = keyed_view.hash_subset(&[7]).await?;
+    assert!(matches!(
+        first.fold(keyed),
+        Err(ViewError::MismatchedSubsets)
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_set_hash_is_independent_of_insertion_order() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut set1 = SetView::<_, u64>::load(context.clone()).await?;
+    let mut set2 = SetView::<_, u64>::load(context).await?;
+    for value in [3, 1, 4, 15, 9, 2, 6] {
+        set1.insert(&value)?;
+    }
+    for value in [15, 9, 6, 4, 3, 2, 1] {
+        set2.insert(&value)?;
+    }
+    assert_eq!(set1.hash().await?, set2.hash().await?);
+    set2.remove(&15)?;
+    assert_ne!(set1.hash().await?, set2.hash().await?);
+    Ok(())
+}
+
+/// Splitting the same bytes differently across the keys must change the hash.
+#[tokio::test]
+async fn check_byte_set_hash_frames_keys() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut set1 = ByteSetView::load(context.clone()).await?;
+    let mut set2 = ByteSetView::load(context).await?;
+    set1.insert(vec![0, 1]);
+    set1.insert(vec![2]);
+    set2.insert(vec![0]);
+    set2.insert(vec![1, 2]);
+    assert_ne!(set1.hash().await?, set2.hash().await?);
+    Ok(())
+}
+
+/// Splitting the same bytes differently between the keys and the values must change the
+/// hash.
+#[tokio::test]
+async fn check_byte_map_hash_frames_keys() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map1 = ByteMapView::<_, u8>::load(context.clone()).await?;
+    let mut map2 = ByteMapView::<_, u8>::load(context).await?;
+    map1.insert(vec![0, 1], 2);
+    map1.insert(vec![3, 4], 5);
+    map2.insert(vec![0], 1);
+    map2.insert(vec![2, 3, 4], 5);
+    assert_ne!(map1.hash().await?, map2.hash().await?);
+    Ok(())
+}
+
+/// Hashing raw keys with a length prefix gives the same result as hashing their BCS
+/// serialization, as long as the keys are ordered the same way, e.g. if they have the
+/// same length.
+#[tokio::test]
+async fn check_byte_map_hash_matches_map_with_byte_keys() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut byte_map = ByteMapView::<_, u64>::load(context.clone()).await?;
+    let mut map = MapView::<_, Vec<u8>, u64>::load(context).await?;
+    for value in [3u64, 1, 4, 1000] {
+        let index = value.to_be_bytes().repeat(128);
+        byte_map.insert(index.clone(), value);
+        map.insert(&index, value)?;
+    }
+    assert_eq!(byte_map.hash().await?, map.hash().await?);
+
+    // The raw keys are only prefixed with their length once.
+    let mut leaves = Vec::new();
+    for value in [1u64, 3, 4, 1000] {
+        let index = value.to_be_bytes().repeat(128);
+        leaves.push(merkle::leaf_hash(&index, &bcs::to_bytes(&value)?)?);
+    }
+    let mut hasher = HashTag::Map.hasher::<sha3::Sha3_256>()?;
+    hasher.update_with_bytes(&merkle::merkle_root(leaves)?)?;
+    assert_eq!(byte_map.hash().await?, hasher.finalize());
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_map_hash_excluding() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    let mut expected_map =
+        MapView::<_, u32, String>::load(context.clone_with_base_key(vec![1])).await?;
+    for index in 0..10u32 {
+        map.insert(&index, format!("value{index}"))?;
+    }
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    map.insert(&10, "value10".to_string())?;
+
+    assert_eq!(
+        map.hash_excluding(&BTreeSet::new()).await?,
+        map.hash().await?
+    );
+
+    let exclude = BTreeSet::from([0, 3, 10, 42]);
+    for index in 0..=10u32 {
+        if !exclude.contains(&index) {
+            expected_map.insert(&index, format!("value{index}"))?;
+        }
+    }
+    assert_eq!(
+        map.hash_excluding(&exclude).await?,
+        expected_map.hash().await?
+    );
+
+    let exclude = (0..=10u32).collect::<BTreeSet<_>>();
+    expected_map.clear();
+    assert_eq!(
+        map.hash_excluding(&exclude).await?,
+        expected_map.hash().await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_map_hash_keys() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    for index in 0..10u32 {
+        map.insert(&index, format!("value{index}"))?;
+    }
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    let hash = map.hash_keys().await?;
+    let full_hash = map.hash().await?;
+
+    // Changing values, stored or pending, does not change the hash of the keys.
+    map.insert(&3, "other value".to_string())?;
+    *map.get_mut(&5).await?.unwrap() = "yet another value".to_string();
+    assert_eq!(map.hash_keys().await?, hash);
+    assert_ne!(map.hash().await?, full_hash);
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    assert_eq!(map.hash_keys().await?, hash);
+
+    // Adding or removing keys does.
+    map.insert(&10, "value10".to_string())?;
+    let added_hash = map.hash_keys().await?;
+    assert_ne!(added_hash, hash);
+    map.remove(&10)?;
+    assert_eq!(map.hash_keys().await?, hash);
+    map.remove(&0)?;
+    assert_ne!(map.hash_keys().await?, hash);
+    map.insert(&0, "new value".to_string())?;
+    assert_eq!(map.hash_keys().await?, hash);
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_map_hash_in_batches() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    let mut byte_map = ByteMapView::<_, u32>::load(context.clone_with_base_key(vec![1])).await?;
+    for index in 0..5000u32 {
+        map.insert(&index, format!("value{index}"))?;
+        byte_map.insert(index.to_be_bytes().to_vec(), index);
+    }
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    byte_map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+
+    // Mix stored entries with pending insertions, updates and removals.
+    for index in (0..6000u32).step_by(7) {
+        map.insert(&index, format!("new value{index}"))?;
+        byte_map.insert(index.to_be_bytes().to_vec(), index + 1);
+    }
+    for index in (0..6000u32).step_by(11) {
+        map.remove(&index)?;
+        byte_map.remove(index.to_be_bytes().to_vec());
+    }
+    let hash = map.hash().await?;
+    let byte_hash = byte_map.hash().await?;
+    for batch_size in [0, 1, 7, 100, 10000] {
+        assert_eq!(map.hash_in_batches(batch_size).await?, hash);
+        assert_eq!(byte_map.hash_in_batches(batch_size).await?, byte_hash);
+    }
+    Ok(())
+}
+
+/// The hash of a map is updated incrementally, and agrees with the hash of a fresh view
+/// with the same entries after any sequence of insertions, removals, flushes and rollbacks.
+#[tokio::test]
+async fn check_map_incremental_hash_matches_fresh_hash() -> Result<()> {
+    let mut rng = make_deterministic_rng();
+    for _ in 0..10 {
+        let context = MemoryContext::new_for_testing(());
+        let mut map = ByteMapView::<_, u32>::load(context.clone()).await?;
+        for _ in 0..50 {
+            for _ in 0..rng.gen_range(1..5) {
+                let key = vec![rng.gen_range(0..4u8), rng.gen_range(0..4u8)];
+                match rng.gen_range(0..10) {
+                    0 => map.remove_by_prefix(key[..1].to_vec()),
+                    1..=3 => map.remove(key),
+                    _ => map.insert(key, rng.gen()),
+                }
+            }
+            match rng.gen_range(0..10) {
+                0 => map.rollback(),
+                1..=3 => {
+                    let mut batch = Batch::new();
+                    map.flush(&mut batch)?;
+                    context.store().write_batch(batch).await?;
+                    let stored_map = ByteMapView::<_, u32>::load(context.clone()).await?;
+                    assert_eq!(map.hash().await?, stored_map.hash().await?);
+                }
+                _ => {}
+            }
+            let hash = map.hash().await?;
+            let mut fresh_map =
+                ByteMapView::<_, u32>::load(context.clone_with_base_key(vec![1])).await?;
+            for (key, value) in map.key_values().await? {
+                fresh_map.insert(key, value);
+            }
+            assert_eq!(fresh_map.hash().await?, hash);
+            assert_eq!(map.hash_in_batches(3).await?, hash);
+            assert_eq!(map.hash_excluding(&BTreeSet::new()).await?, hash);
+        }
+    }
+    Ok(())
+}
+
+/// Hashing a view with several hashers in a single pass gives the same results as hashing
+/// it with each hasher alone, and the same encoding as `HashableView::hash`.
+#[tokio::test]
+async fn check_hash_multi() -> Result<()> {
+    async fn check<V: MultiHashableView<MemoryContext<()>, Hasher = sha3::Sha3_256> + Sync>(
+        view: &V,
+    ) -> Result<()> {
+        let mut sha3_hasher = sha3::Sha3_256::default();
+        let mut sha256_hasher = sha2::Sha256::default();
+        let mut blake3_hasher = blake3::Hasher::default();
+        let hashes = view
+            .hash_multi(&mut [&mut sha3_hasher, &mut sha256_hasher, &mut blake3_hasher])
+            .await?;
+        assert_eq!(hashes[0], view.hash().await?.to_vec());
+        assert_eq!(
+            hashes[1..],
+            [
+                view.hash_multi(&mut [&mut sha256_hasher]).await?.remove(0),
+                view.hash_multi(&mut [&mut blake3_hasher]).await?.remove(0),
+            ]
+        );
+        assert_ne!(hashes[1], hashes[2]);
+        Ok(())
+    }
+
+    for context in [
+        MemoryContext::new_for_testing(()),
+        MemoryContext::new_for_testing(()).with_hash_key([7; 32]),
+    ] {
+        let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+        for index in 0..100u32 {
+            map.insert(&index, format!("value{index}"))?;
+        }
+        let mut batch = Batch::new();
+        map.flush(&mut batch)?;
+        context.store().write_batch(batch).await?;
+        map.insert(&7, "new value".to_string())?;
+        map.insert(&200, "value200".to_string())?;
+        map.remove(&3)?;
+        check(&map).await?;
+
+        let mut byte_map =
+            ByteMapView::<_, String>::load(context.clone_with_base_key(vec![1])).await?;
+        byte_map.insert(vec![0, 1], "value".to_string());
+        check(&byte_map).await?;
+
+        let mut collection = CollectionView::<_, u32, MapView<_, u32, String>>::load(
+            context.clone_with_base_key(vec![2]),
+        )
+        .await?;
+        for outer in 0..5u32 {
+            let inner_map = collection.load_entry_mut(&outer).await?;
+            for inner in 0..outer {
+                inner_map.insert(&inner, format!("value{outer}-{inner}"))?;
+            }
+        }
+        let mut batch = Batch::new();
+        collection.flush(&mut batch)?;
+        context.store().write_batch(batch).await?;
+        collection
+            .load_entry_mut(&1)
+            .await?
+            .insert(&9, "new value".to_string())?;
+        check(&collection).await?;
+
+        let collection = CollectionView::<_, u32, RegisterView<_, u64>>::load(
+            context.clone_with_base_key(vec![3]),
+        )
+        .await?;
+        check(&collection).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_log_merkle_proofs() -> Result<()> {
+    type TestLog = LogView<MemoryContext<()>, u64>;
+
+    let mut roots = HashSet::new();
+    for size in 0..10u64 {
+        let context = MemoryContext::new_for_testing(());
+        let mut log = TestLog::load(context.clone()).await?;
+        // Save half of the values, so that the others are only in memory.
+        for value in 0..size / 2 {
+            log.push(value * 10);
+        }
+        let mut batch = Batch::new();
+        log.flush(&mut batch)?;
+        context.store().write_batch(batch).await?;
+        for value in size / 2..size {
+            log.push(value * 10);
+        }
+        let root = log.merkle_root().await?;
+        assert!(roots.insert(root));
+
+        let mut batch = Batch::new();
+        log.flush(&mut batch)?;
+        context.store().write_batch(batch).await?;
+        let log = TestLog::load(context).await?;
+        assert_eq!(log.merkle_root().await?, root);
+
+        for index in 0..size {
+            let proof = log.prove(index as usize).await?.unwrap();
+            let value = index * 10;
+            assert!(TestLog::verify_proof(
+                &root,
+                index as usize,
+                &value,
+                &proof
+            )?);
+            assert!(!TestLog::verify_proof(
+                &root,
+                index as usize,
+                &(value + 1),
+                &proof
+            )?);
+            assert!(!TestLog::verify_proof(
+                &root,
+                index as usize + 1,
+                &value,
+                &proof
+            )?);
+        }
+        assert!(log.prove(size as usize).await?.is_none());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_map_merkle_proofs() -> Result<()> {
+    type TestMap = MapView<MemoryContext<()>, u32, String>;
+
+    for size in 1..10u32 {
+        let context = MemoryContext::new_for_testing(());
+        let mut map = TestMap::load(context).await?;
+        for index in 0..size {
+            map.insert(&index, format!("value{index}"))?;
+        }
+        let root = map.merkle_root().await?;
+        for index in 0..size {
+            let proof = map.prove(&index).await?.unwrap();
+            let value = format!("value{index}");
+            assert!(TestMap::verify_proof(&root, &index, &value, &proof)?);
+            let tampered_value = format!("value{}", index + 1);
+            assert!(!TestMap::verify_proof(
+                &root,
+                &index,
+                &tampered_value,
+                &proof
+            )?);
+            assert!(!TestMap::verify_proof(&root, &(index + 1), &value, &proof)?);
+        }
+        assert!(map.prove(&size).await?.is_none());
+    }
+    Ok(())
+}
+
+/// The proofs of a map are checked against the same root as the one committed by its hash,
+/// including when the hashes are keyed.
+#[tokio::test]
+async fn check_map_merkle_proofs_match_hash() -> Result<()> {
+    type TestMap = MapView<MemoryContext<()>, u32, String>;
+    type TestByteMap = ByteMapView<MemoryContext<()>, String>;
+
+    let hash_key = [7; 32];
+    let context = MemoryContext::new_for_testing(()).with_hash_key(hash_key);
+    let mut map = TestMap::load(context.clone_with_base_key(vec![0])).await?;
+    let mut byte_map = TestByteMap::load(context.clone_with_base_key(vec![1])).await?;
+    for index in 0..5u32 {
+        map.insert(&index, format!("value{index}"))?;
+        byte_map.insert(
+            vec![index as u8; index as usize + 1],
+            format!("value{index}"),
+        );
+    }
+
+    let root = map.merkle_root().await?;
+    let mut hasher = HashTag::Map.keyed_hasher::<sha3::Sha3_256>(Some(&hash_key))?;
+    hasher.update_with_bytes(&root)?;
+    assert_eq!(map.hash().await?, hasher.finalize());
+    for index in 0..5u32 {
+        let proof = map.prove(&index).await?.unwrap();
+        let value = format!("value{index}");
+        assert!(TestMap::verify_proof(&root, &index, &value, &proof)?);
+    }
+
+    let root = byte_map.merkle_root().await?;
+    let mut hasher = HashTag::Map.keyed_hasher::<sha3::Sha3_256>(Some(&hash_key))?;
+    hasher.update_with_bytes(&root)?;
+    assert_eq!(byte_map.hash().await?, hasher.finalize());
+    for index in 0..5u32 {
+        let key = vec![index as u8; index as usize + 1];
+        let proof = byte_map.prove(&key).await?.unwrap();
+        let value = format!("value{index}");
+        assert!(TestByteMap::verify_proof(&root, &key, &value, &proof)?);
+    }
+    Ok(())
+}
+
+/// `HashableView::hash` only needs a shared reference, and agrees with `hash_mut`.
+#[tokio::test]
+async fn check_shared_hash_matches_mutable_hash() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context.clone()).await?;
+    register.set(5);
+    assert_eq!(register.hash().await?, register.hash_mut().await?);
+
+    let mut log = LogView::<_, u64>::load(context.clone()).await?;
+    log.push(3);
+    log.push(4);
+    assert_eq!(log.hash().await?, log.hash_mut().await?);
+
+    let mut map = MapView::<_, u32, u64>::load(context).await?;
+    map.insert(&7, 8)?;
+    assert_eq!(map.hash().await?, map.hash_mut().await?);
+    Ok(())
+}
+
+/// Views of different kinds holding the same serialized payload must not hash identically.
+#[tokio::test]
+async fn check_hash_tags_separate_view_kinds() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, Vec<u8>>::load(context.clone()).await?;
+    register.set(vec![1, 2, 3]);
+    let mut log = LogView::<_, u8>::load(context).await?;
+    for value in [1, 2, 3] {
+        log.push(value);
+    }
+    assert_ne!(register.hash().await?, log.hash().await?);
+    Ok(())
+}
+
+fn check_reset_hasher<H: Hasher>() -> Result<()> {
+    let mut reused_hasher = H::default();
+    reused_hasher.update_with_bytes(b"previous data")?;
+    reused_hasher.reset();
+    reused_hasher.update_with_bytes(b"data")?;
+    let mut fresh_hasher = H::default();
+    fresh_hasher.update_with_bytes(b"data")?;
+    assert_eq!(reused_hasher.finalize(), fresh_hasher.finalize());
+
+    // Resetting with a key gives a keyed hasher, and resetting without one drops the key.
+    let mut reused_hasher = H::new_keyed(&[1; 32])?;
+    reused_hasher.update_with_bytes(b"previous data")?;
+    reused_hasher.reset_with_key(Some(&[2; 32]))?;
+    reused_hasher.update_with_bytes(b"data")?;
+    let mut fresh_hasher = H::new_keyed(&[2; 32])?;
+    fresh_hasher.update_with_bytes(b"data")?;
+    assert_eq!(reused_hasher.finalize(), fresh_hasher.finalize());
+    let mut reused_hasher = H::new_keyed(&[1; 32])?;
+    reused_hasher.reset_with_key(None)?;
+    reused_hasher.update_with_bytes(b"data")?;
+    let mut fresh_hasher = H::default();
+    fresh_hasher.update_with_bytes(b"data")?;
+    assert_eq!(reused_hasher.finalize(), fresh_hasher.finalize());
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_reset_hashers() -> Result<()> {
+    check_reset_hasher::<sha3::Sha3_256>()?;
+    check_reset_hasher::<sha3::Keccak256>()?;
+    check_reset_hasher::<sha2::Sha256>()?;
+    check_reset_hasher::<blake3::Hasher>()?;
+
+    let context = MemoryContext::new_for_testing(());
+    let mut hasher = sha3::Sha3_256::default();
+    hasher.update_with_bytes(b"previous data")?;
+    for value in 0..3 {
+        let mut register = RegisterView::<_, u64>::load(context.clone()).await?;
+        register.set(value);
+        assert_eq!(register.hash_with(&mut hasher)?, register.hash().await?);
+    }
+
+    // With a keyed context, the key is used too.
+    let keyed_context = MemoryContext::new_for_testing(()).with_hash_key([7; 32]);
+    for value in 0..3 {
+        let mut register = RegisterView::<_, u64>::load(keyed_context.clone()).await?;
+        register.set(value);
+        let hash = register.hash_with(&mut hasher)?;
+        assert_eq!(hash, register.hash().await?);
+        let mut unkeyed_register = RegisterView::<_, u64>::load(context.clone()).await?;
+        unkeyed_register.set(value);
+        assert_ne!(hash, unkeyed_register.hash().await?);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_hash_hex_round_trip() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = RegisterView::<_, u64>::load(context).await?;
+    view.set(42);
+    let hash = view.hash().await?;
+    let hex = hash_to_hex::<sha3::Sha3_256>(&hash);
+    assert_eq!(hex.len(), 64);
+    assert!(!hex.starts_with("0x"));
+    assert_eq!(hex, hex.to_lowercase());
+    assert_eq!(hash_from_hex::<sha3::Sha3_256>(&hex)?, hash);
+
+    let blake3_hash = hash_register::<blake3::Hasher>(&view)?;
+    let blake3_hex = hash_to_hex::<blake3::Hasher>(&blake3_hash);
+    assert_eq!(hash_from_hex::<blake3::Hasher>(&blake3_hex)?, blake3_hash);
+
+    assert!(hash_from_hex::<sha3::Sha3_256>(&hex[2..]).is_err());
+    assert!(hash_from_hex::<sha3::Sha3_256>("not hexadecimal").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_log_hash_range() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut log = LogView::<_, u64>::load(context.clone()).await?;
+    for value in 0..8 {
+        log.push(value);
+    }
+    let empty_log = LogView::<_, u64>::load(context.clone()).await?;
+    let empty_hash = empty_log.hash().await?;
+    assert_eq!(log.hash_range(0..0).await?, empty_hash);
+    assert_eq!(log.hash_range(5..5).await?, empty_hash);
+
+    let mut partial_log = LogView::<_, u64>::load(context).await?;
+    for value in 2..5 {
+        partial_log.push(value);
+    }
+    assert_eq!(log.hash_range(2..5).await?, partial_log.hash().await?);
+    assert_eq!(log.hash_range(0..8).await?, log.hash().await?);
+
+    assert!(log.hash_range(6..9).await.is_err());
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed_range = 5..2;
+    assert!(log.hash_range(reversed_range).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_log_hash_resumable() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut log = LogView::<_, String>::load(context.clone()).await?;
+    for index in 0..200 {
+        log.push(format!("value{index}"));
+    }
+    let mut batch = Batch::new();
+    log.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+    // Some values are stored and the others are staged.
+    for index in 200..300 {
+        log.push(format!("value{index}"));
+    }
+    let hash = log.hash().await?;
+
+    // Each slice is rounded up to whole chunks, and the states are serialized in between.
+    let HashProgress::Pending(state) = log.hash_resumable(None, 120).await? else {
+        panic!("the first slice should not complete the hash");
+    };
+    assert_eq!(state.position(), 2 * HASH_CHUNK_SIZE);
+    let state = bcs::from_bytes(&bcs::to_bytes(&state)?)?;
+    let HashProgress::Pending(state) = log.hash_resumable(Some(state), 120).await? else {
+        panic!("the second slice should not complete the hash");
+    };
+    assert_eq!(state.position(), 4 * HASH_CHUNK_SIZE);
+    let state = bcs::from_bytes(&bcs::to_bytes(&state)?)?;
+    let HashProgress::Done(resumed_hash) = log.hash_resumable(Some(state), 120).await? else {
+        panic!("the third slice should complete the hash");
+    };
+    assert_eq!(resumed_hash, hash);
+
+    // A state must stop at the end of a chunk of the log.
+    let invalid_state: HashState = bcs::from_bytes(&bcs::to_bytes(&(5usize, hash))?)?;
+    assert!(log.hash_resumable(Some(invalid_state), 120).await.is_err());
+
+    let HashProgress::Done(one_shot_hash) = log.hash_resumable(None, usize::MAX).await? else {
+        panic!("a single slice should complete the hash");
+    };
+    assert_eq!(one_shot_hash, hash);
+
+    let empty_log = LogView::<_, String>::load(context.clone_with_base_key(vec![1])).await?;
+    let HashProgress::Done(empty_hash) = empty_log.hash_resumable(None, 0).await? else {
+        panic!("an empty log should be hashed at once");
+    };
+    assert_eq!(empty_hash, empty_log.hash().await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_hash_serializable_matches_views() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context.clone()).await?;
+    register.set(42);
+    let expected = HashTag::Register.hash_serializable::<sha3::Sha3_256, _>(&42u64)?;
+    assert_eq!(register.hash().await?, expected);
+    assert_eq!(hash_register::<sha3::Sha3_256>(&register)?, expected);
+
+    // Both kinds of queues commit to the sequence of their elements in the same way.
+    let mut queue = QueueView::<_, u64>::load(context.clone()).await?;
+    let mut bucket_queue = BucketQueueView::<_, u64, 2>::load(context.clone()).await?;
+    for value in 0..5 {
+        queue.push_back(value);
+        bucket_queue.push_back(value);
+    }
+    let expected =
+        HashTag::Queue.hash_serializable::<sha3::Sha3_256, _>(&vec![0u64, 1, 2, 3, 4])?;
+    assert_eq!(queue.hash().await?, expected);
+    assert_eq!(bucket_queue.hash().await?, expected);
+    Ok(())
+}
+
+async fn map_checksum(map: &MapView<MemoryContext<()>, u32, String>) -> Result<[u8; 16]> {
+    let mut hasher = Xxh3Hasher::default();
+    map.for_each_index_value(|index, value| {
+        hasher.update_with_bcs_bytes(&index)?;
+        hasher.update_with_bcs_bytes(&value)?;
+        Ok(())
+    })
+    .await?;
+    Ok(hasher.finalize())
+}
+
+fn check_block_buffered_hasher<H: Hasher>() -> Result<()> {
+    let mut rng = make_deterministic_rng();
+    let chunks = (0..200)
+        .map(|_| {
+            let len = match rng.gen_range(0..4) {
+                0 => rng.gen_range(0..8),
+                1 => H::BLOCK_SIZE,
+                2 => rng.gen_range(0..10 * H::BLOCK_SIZE),
+                _ => rng.gen_range(0..100 * H::BLOCK_SIZE),
+            };
+            (0..len).map(|_| rng.gen()).collect::<Vec<u8>>()
+        })
+        .collect::<Vec<_>>();
+    let mut writer = BlockBufferedHasher::new(H::default());
+    let mut hasher = H::default();
+    for chunk in &chunks {
+        writer.write_all(chunk)?;
+        hasher.update_with_bytes(chunk)?;
+    }
+    assert_eq!(writer.into_inner()?.finalize(), hasher.finalize());
+    Ok(())
+}
+
+/// Feeding a hasher by whole blocks does not change the hash.
+#[test]
+fn check_block_buffered_hashers() -> Result<()> {
+    check_block_buffered_hasher::<sha3::Sha3_256>()?;
+    check_block_buffered_hasher::<sha2::Sha256>()?;
+    check_block_buffered_hasher::<sha3::Keccak256>()?;
+    check_block_buffered_hasher::<blake3::Hasher>()?;
+    check_block_buffered_hasher::<Xxh3Hasher>()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_xxh3_checksums() -> Result<()> {
+    let checksum = |bytes: &[u8]| -> Result<[u8; 16]> {
+        let mut hasher = Xxh3Hasher::default();
+        hasher.update_with_bytes(bytes)?;
+        Ok(hasher.finalize())
+    };
+    assert_eq!(checksum(b"linera")?, checksum(b"linera")?);
+    assert_ne!(checksum(b"linera")?, checksum(b"linerb")?);
+    assert_ne!(checksum(b"")?, checksum(b"\0")?);
+
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context).await?;
+    map.insert(&1, "one".to_string())?;
+    map.insert(&2, "two".to_string())?;
+    let snapshot = map_checksum(&map).await?;
+    assert_eq!(snapshot, map_checksum(&map).await?);
+    map.insert(&2, "deux".to_string())?;
+    assert_ne!(snapshot, map_checksum(&map).await?);
+    map.insert(&2, "two".to_string())?;
+    assert_eq!(snapshot, map_checksum(&map).await?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_view_hash_serialization() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut register = RegisterView::<_, u64>::load(context).await?;
+    register.set(7);
+    let hash = ViewHash::of(&register).await?;
+    assert_eq!(hash.as_ref(), register.hash().await?.as_slice());
+
+    let bytes = bcs::to_bytes(&hash)?;
+    assert_eq!(bcs::from_bytes::<ViewHash>(&bytes)?, hash);
+    let json = serde_json::to_string(&hash)?;
+    assert_eq!(json, format!("\"{hash}\""));
+    assert_eq!(serde_json::from_str::<ViewHash>(&json)?, hash);
+
+    let long_hash = ViewHash::try_from([1; 64].as_slice())?;
+    assert_eq!(
+        bcs::from_bytes::<ViewHash>(&bcs::to_bytes(&long_hash)?)?,
+        long_hash
+    );
+    assert!(ViewHash::try_from([1; 16].as_slice()).is_err());
+    assert!(bcs::from_bytes::<ViewHash>(&bcs::to_bytes(&[1u8; 16].as_slice())?).is_err());
+    Ok(())
+}
+
+#[test]
+fn check_view_hash_ordering() -> Result<()> {
+    let low = ViewHash::try_from([0; 32].as_slice())?;
+    let high = ViewHash::try_from([0xff; 32].as_slice())?;
+    let long = ViewHash::try_from([0; 64].as_slice())?;
+    assert!(low < high);
+    // Hashes are ordered by their bytes, so a prefix comes first.
+    assert!(low < long);
+    assert!(long < high);
+    let mut hashes = vec![high, long, low];
+    hashes.sort();
+    assert_eq!(hashes, vec![low, long, high]);
+    Ok(())
+}
+
+/// After a rollback, the hashes reflect the saved state, even if the staged changes were
+/// hashed before.
+#[tokio::test]
+async fn check_hashes_after_rollback() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut batch = Batch::new();
+
+    let mut register =
+        HashedRegisterView::<_, u64>::load(context.clone_with_base_key(vec![0])).await?;
+    register.set(1);
+    register.flush(&mut batch)?;
+    let mut log = LogView::<_, u64>::load(context.clone_with_base_key(vec![1])).await?;
+    log.push(1);
+    log.push(2);
+    log.flush(&mut batch)?;
+    let mut collection =
+        ByteCollectionView::<_, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![2]))
+            .await?;
+    collection.load_entry_mut(&[1]).await?.set(1);
+    collection.load_entry_mut(&[2]).await?.set(2);
+    collection.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+
+    let register_hash = register.hash().await?;
+    let log_hash = log.hash().await?;
+    let collection_hash = collection.hash().await?;
+
+    register.set(2);
+    assert_ne!(register.hash().await?, register_hash);
+    register.rollback();
+    assert_eq!(register.hash().await?, register_hash);
+    assert_eq!(register.hash_mut().await?, register_hash);
+
+    log.push(3);
+    assert_ne!(log.hash().await?, log_hash);
+    log.rollback();
+    assert_eq!(log.hash().await?, log_hash);
+    log.clear();
+    assert_ne!(log.hash().await?, log_hash);
+    log.rollback();
+    assert_eq!(log.hash().await?, log_hash);
+
+    collection.load_entry_mut(&[1]).await?.set(3);
+    collection.load_entry_mut(&[3]).await?.set(3);
+    collection.remove_entry(vec![2]);
+    assert_ne!(collection.hash().await?, collection_hash);
+    collection.rollback();
+    assert_eq!(collection.hash().await?, collection_hash);
+    assert_eq!(collection.hash_mut().await?, collection_hash);
+    Ok(())
+}
+
+fn check_hash_set_of_outputs<H: Hasher>() -> Result<()> {
+    let mut hashes = HashSet::new();
+    for (value, is_new) in [(1u64, true), (2, true), (1, false), (3, true), (2, false)] {
+        let hash = HashTag::Register.hash_serializable::<H, _>(&value)?;
+        assert_eq!(hashes.insert(hash), is_new);
+    }
+    assert_eq!(hashes.len(), 3);
+    Ok(())
+}
+
+/// Hash outputs can be used as keys of sets and maps, e.g. to deduplicate identical views.
+#[tokio::test]
+async fn check_hash_outputs_in_hash_sets() -> Result<()> {
+    check_hash_set_of_outputs::<sha3::Sha3_256>()?;
+    check_hash_set_of_outputs::<sha3::Keccak256>()?;
+    check_hash_set_of_outputs::<sha2::Sha256>()?;
+    check_hash_set_of_outputs::<blake3::Hasher>()?;
+    check_hash_set_of_outputs::<Xxh3Hasher>()?;
+
+    let context = MemoryContext::new_for_testing(());
+    let mut registers = Vec::new();
+    for (index, value) in [7u64, 8, 7].into_iter().enumerate() {
+        let mut register =
+            RegisterView::<_, u64>::load(context.clone_with_base_key(vec![index as u8])).await?;
+        register.set(value);
+        registers.push(register);
+    }
+    let mut hashes = HashSet::new();
+    for register in &registers {
+        hashes.insert(register.hash().await?);
+    }
+    assert_eq!(hashes.len(), 2);
+    Ok(())
+}
+
+fn check_output_serialization<H: Hasher>() -> Result<()> {
+    let mut hasher = H::default();
+    hasher.update_with_bytes(b"Hello")?;
+    let output = hasher.finalize();
+    let bytes = bcs::to_bytes(&output)?;
+    assert_eq!(bcs::from_bytes::<H::Output>(&bytes)?, output);
+    let json = serde_json::to_string(&output)?;
+    assert_eq!(serde_json::from_str::<H::Output>(&json)?, output);
+    Ok(())
+}
+
+/// Hash outputs can be serialized, e.g. to store the hash of a view in another view.
+#[tokio::test]
+async fn check_hash_outputs_serialization() -> Result<()> {
+    check_output_serialization::<sha3::Sha3_256>()?;
+    check_output_serialization::<sha3::Keccak256>()?;
+    check_output_serialization::<sha2::Sha256>()?;
+    check_output_serialization::<blake3::Hasher>()?;
+    check_output_serialization::<Xxh3Hasher>()?;
+
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context.clone_with_base_key(vec![0])).await?;
+    map.insert(&1, "one".to_string())?;
+    let mut root = RegisterView::<_, Option<<sha2::Sha256 as Hasher>::Output>>::load(
+        context.clone_with_base_key(vec![1]),
+    )
+    .await?;
+    let mut hasher = sha2::Sha256::default();
+    hasher.update_with_bytes(map.hash().await?.as_ref())?;
+    let digest = hasher.finalize();
+    root.set(Some(digest));
+    let mut batch = Batch::new();
+    root.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+
+    let root = RegisterView::<_, Option<<sha2::Sha256 as Hasher>::Output>>::load(
+        context.clone_with_base_key(vec![1]),
+    )
+    .await?;
+    assert_eq!(root.get(), &Some(digest));
+    Ok(())
+}
+
+/// Computes the hashes of empty views of each kind of collection in the given context.
+async fn empty_collection_hashes(context: MemoryContext<()>) -> Result<Vec<HasherOutput>> {
+    let map = MapView::<_, u32, String>::load(context.clone_with_base_key(vec![0])).await?;
+    let set = SetView::<_, u32>::load(context.clone_with_base_key(vec![1])).await?;
+    let log = LogView::<_, u32>::load(context.clone_with_base_key(vec![2])).await?;
+    let queue = QueueView::<_, u32>::load(context.clone_with_base_key(vec![3])).await?;
+    let bucket_queue =
+        BucketQueueView::<_, u32, 2>::load(context.clone_with_base_key(vec![4])).await?;
+    let collection =
+        CollectionView::<_, u32, RegisterView<_, u64>>::load(context.clone_with_base_key(vec![5]))
+            .await?;
+    let empty_subset = collection.hash_subset(&[0, 1]).await?;
+    let subset_hash = empty_subset.clone().fold(empty_subset)?.hash()?;
+    let HashProgress::Done(resumable_log_hash) = log.hash_resumable(None, 1).await? else {
+        panic!("an empty log is hashed in one step");
+    };
+    Ok(vec![
+        map.hash().await?,
+        map.hash_in_batches(1).await?,
+        set.hash().await?,
+        log.hash().await?,
+        log.hash_range(0..0).await?,
+        resumable_log_hash,
+        queue.hash().await?,
+        bucket_queue.hash().await?,
+        collection.hash().await?,
+        subset_hash,
+    ])
+}
+
+/// With `empty_sentinel` set, all empty collection views have the same fixed hash.
+#[tokio::test]
+async fn check_empty_sentinel() -> Result<()> {
+    let sentinel = HasherOutput::from(EMPTY_SENTINEL_HASH);
+    let hashes = empty_collection_hashes(MemoryContext::new_for_testing(())).await?;
+    assert!(hashes.iter().all(|hash| *hash != sentinel));
+    assert_ne!(hashes[0], hashes[2]);
+    assert_ne!(hashes[2], hashes[3]);
+
+    let context = MemoryContext::new_for_testing(()).with_empty_sentinel();
+    let hashes = empty_collection_hashes(context.clone()).await?;
+    assert!(hashes.iter().all(|hash| *hash == sentinel));
+
+    // Non-empty views are not affected.
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    map.insert(&1, "one".to_string())?;
+    let mut plain_map = MapView::<_, u32, String>::load(MemoryContext::new_for_testing(())).await?;
+    plain_map.insert(&1, "one".to_string())?;
+    assert_eq!(map.hash().await?, plain_map.hash().await?);
+    let mut log = LogView::<_, u32>::load(context).await?;
+    log.push(7);
+    let mut plain_log = LogView::<_, u32>::load(MemoryContext::new_for_testing(())).await?;
+    plain_log.push(7);
+    assert_eq!(log.hash().await?, plain_log.hash().await?);
+    assert_ne!(log.hash().await?, sentinel);
+    Ok(())
+}
+
+/// Hashing a stream of views folds their hashes in order, followed by their number.
+#[tokio::test]
+async fn check_hash_view_stream() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut registers = Vec::new();
+    for (index, value) in [7u64, 8, 7].into_iter().enumerate() {
+        let mut register =
+            RegisterView::<_, u64>::load(context.clone_with_base_key(vec![index as u8])).await?;
+        register.set(value);
+        registers.push(register);
+    }
+    let mut hasher = HashTag::ViewStream.hasher::<sha3::Sha3_256>()?;
+    for register in &registers {
+        hasher.update_with_bcs_bytes(&register.hash().await?.as_slice())?;
+    }
+    hasher.update_with_bcs_bytes(&(registers.len() as u64))?;
+    let expected = hasher.finalize();
+
+    let stream = futures::stream::iter(registers.into_iter().map(Ok::<_, ViewError>));
+    assert_eq!(hash_view_stream(stream).await?, expected);
+
+    let stream = futures::stream::iter(Vec::<Result<RegisterView<_, u64>, ViewError>>::new());
+    let empty_hash = hash_view_stream(stream).await?;
+    assert_ne!(empty_hash, expected);
+
+    let stream = futures::stream::iter([Err::<RegisterView<MemoryContext<()>, u64>, _>(
+        ViewError::NotFound("page".to_string()),
+    )]);
+    assert!(hash_view_stream(stream).await.is_err());
+    Ok(())
+}
+
+/// A value that always fails to serialize.
+struct Unserializable;
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("not serializable"))
+    }
+}
+
+/// Hashing failures are classified by `HashError`, and converted back to `ViewError`.
+#[tokio::test]
+async fn check_hash_error() -> Result<()> {
+    let error = HashTag::Register
+        .hash_serializable::<sha3::Sha3_256, _>(&Unserializable)
+        .unwrap_err();
+    let error = HashError::from(error);
+    assert!(matches!(error, HashError::Serialization(_)));
+    assert!(matches!(ViewError::from(error), ViewError::BcsError(_)));
+
+    assert!(matches!(
+        HashError::from(ViewError::MissingEntries),
+        HashError::MissingValue(_)
+    ));
+    assert!(matches!(
+        HashError::from(ViewError::Cancelled),
+        HashError::Cancelled
+    ));
+    let error = HashError::from(ViewError::KeyTooLong);
+    assert!(matches!(error, HashError::Backend(_)));
+    assert!(matches!(ViewError::from(error), ViewError::KeyTooLong));
+    Ok(())
+}
+
+/// The same contents hash differently under different schema versions.
+#[tokio::test]
+async fn check_versioned_hash() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, u32, String>::load(context.clone()).await?;
+    map.insert(&1, "one".to_string())?;
+    let mut batch = Batch::new();
+    map.flush(&mut batch)?;
+    context.store().write_batch(batch).await?;
+
+    let version1 = Versioned::<MapView<_, u32, String>, 1>::load(context.clone()).await?;
+    let other_version1 = Versioned::<MapView<_, u32, String>, 1>::load(context.clone()).await?;
+    let mut version2 = Versioned::<MapView<_, u32, String>, 2>::load(context.clone()).await?;
+    assert_eq!(version1.get(&1).await?, Some("one".to_string()));
+    assert_eq!(version1.inner().hash().await?, map.hash().await?);
+    assert_eq!(version2.inner().hash().await?, map.hash().await?);
+    assert_eq!(version1.hash().await?, other_version1.hash().await?);
+    assert_ne!(version1.hash().await?, version2.hash().await?);
+    assert_ne!(version1.hash().await?, map.hash().await?);
+    assert_eq!(version2.hash_mut().await?, version2.hash().await?);
+
+    // The version hash is keyed with the hash key of the context.
+    let keyed_version1 =
+        Versioned::<MapView<_, u32, String>, 1>::load(context.with_hash_key([5; 32])).await?;
+    let mut hasher = HashTag::Versioned.keyed_hasher::<sha3::Sha3_256>(Some(&[5; 32]))?;
+    hasher.update_with_bcs_bytes(&1u32)?;
+    hasher.update_with_bcs_bytes(&keyed_version1.inner().hash().await?.as_ref())?;
+    assert_eq!(keyed_version1.hash().await?, hasher.finalize());
+    assert_ne!(keyed_version1.hash().await?, version1.hash().await?);
+    Ok(())
+}
+
+/// Computes the hash of a map holding `value`.
+async fn hash_float_map(value: f64) -> Result<HasherOutput> {
+    let context = MemoryContext::new_for_testing(());
+    let mut map = MapView::<_, String, CanonicalFloat>::load(context).await?;
+    map.insert(&"metric".to_string(), CanonicalFloat::from(value))?;
+    Ok(map.hash().await?)
+}
+
+/// Floats with several encodings of the same value have a single hash.
+#[tokio::test]
+async fn check_canonical_float_hash() -> Result<()> {
+    assert_eq!(hash_float_map(-0.0).await?, hash_float_map(0.0).await?);
+    let quiet_nan = f64::from_bits(0x7ff8_0000_0000_0001);
+    let negative_nan = f64::from_bits(0xfff8_0000_0000_0000);
+    assert!(quiet_nan.is_nan() && negative_nan.is_nan());
+    assert_ne!(quiet_nan.to_bits(), negative_nan.to_bits());
+    assert_eq!(
+        hash_float_map(quiet_nan).await?,
+        hash_float_map(negative_nan).await?
+    );
+    assert_eq!(
+        hash_float_map(quiet_nan).await?,
+        hash_float_map(f64::NAN).await?
+    );
+    assert_ne!(hash_float_map(1.5).await?, hash_float_map(0.0).await?);
+
+    // Deserialized values are canonical too.
+    let bytes = bcs::to_bytes(&negative_nan.to_bits())?;
+    let value = bcs::from_bytes::<CanonicalFloat>(&bytes)?;
+    assert_eq!(value.get().to_bits(), f64::NAN.to_bits());
+    let value = serde_json::from_str::<CanonicalFloat>("\"-0\"")?;
+    assert_eq!(value.get().to_bits(), 0);
+    assert_eq!(
+        serde_json::to_string(&CanonicalFloat::from(1.5))?,
+        "\"1.5\""
+    );
+
+    // NaN and infinities survive a JSON round trip.
+    for value in [negative_nan, f64::INFINITY, f64::NEG_INFINITY, 1.5, -0.0] {
+        let value = CanonicalFloat::from(value);
+        let json = serde_json::to_string(&value)?;
+        assert_eq!(serde_json::from_str::<CanonicalFloat>(&json)?, value);
+    }
+    Ok(())
+}